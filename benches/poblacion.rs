@@ -0,0 +1,186 @@
+// Mide cuánto tarda el motor con poblaciones grandes y compara las consultas
+// (censo, conteos y búsquedas por ID) de las tablas por especie con las del
+// diseño anterior, un `Box<dyn Organismo>` por individuo en un solo vector.
+// Uso: cargo bench --bench poblacion [-- TAMAÑO... [--dias N]]
+// Sin tamaños corre 10 mil, 100 mil y un millón de individuos.
+
+use std::collections::BTreeMap;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use juego_mejorado::argumentos::PoblacionInicial;
+use juego_mejorado::escenario::Escenario;
+use juego_mejorado::espacio::Espacio;
+use juego_mejorado::herbivoro::Herbivoro;
+use juego_mejorado::lobo::Lobo;
+use juego_mejorado::organismo::Organismo;
+use juego_mejorado::poblacion::Poblacion;
+
+// El escenario por defecto escalado a `n` individuos: nueve cabras por
+// conejo, un lobo cada mil presas (con hambre desde el primer día) y pasto
+// de sobra para que no colapse.
+// Con espacio, la grilla tiene unas cuatro presas por celda.
+fn escenario(n: u32, espacial: bool) -> Escenario {
+    let mut escenario = Escenario { semilla: Some(7), ..Escenario::default() };
+    let inicial = |aleatorios| PoblacionInicial { aleatorios, ..Default::default() };
+    escenario.poblacion.insert("Cabra".to_string(), inicial(n / 10 * 9));
+    escenario.poblacion.insert("Conejo".to_string(), inicial(n / 10));
+    escenario.poblacion.insert("Lobo".to_string(), inicial((n / 1000).max(1)));
+    escenario.lobo.reserva_inicial = escenario.lobo.reserva_minima - 5.0;
+    escenario.vegetacion.capacidad = n as f64 * 500.0;
+    escenario.vegetacion.biomasa_inicial = escenario.vegetacion.capacidad;
+    if espacial {
+        let lado = ((n as f64 / 4.0).sqrt() as u32).max(10);
+        escenario.espacio = Some(Espacio { ancho: lado, alto: lado, ..Espacio::default() });
+    }
+    escenario
+}
+
+// Arma el simulador y corre `dias` días; devuelve ambos tiempos.
+fn medir(n: u32, espacial: bool, dias: u32) -> (Duration, Duration, usize) {
+    let escenario = escenario(n, espacial);
+    let inicio = Instant::now();
+    let mut sim = escenario.crear_simulador(None);
+    let armado = inicio.elapsed();
+
+    let inicio = Instant::now();
+    for dia in 1..=dias {
+        sim.simular_dia(dia);
+        // Lo que la interfaz consulta en cada cuadro.
+        black_box((sim.contar::<Herbivoro>(), sim.contar::<Lobo>(), sim.censo()));
+    }
+    (armado, inicio.elapsed(), sim.contar::<Herbivoro>() + sim.contar::<Lobo>())
+}
+
+// Las consultas que se miden, con las tablas por especie y con el diseño anterior.
+trait Consultas {
+    fn censo(&self) -> BTreeMap<String, usize>;
+    fn contar_especie(&self, especie: &str) -> usize;
+    fn buscar(&self, id: u64) -> Option<&dyn Organismo>;
+}
+
+impl Consultas for Poblacion {
+    fn censo(&self) -> BTreeMap<String, usize> {
+        Poblacion::censo(self)
+    }
+
+    fn contar_especie(&self, especie: &str) -> usize {
+        Poblacion::contar_especie(self, especie)
+    }
+
+    fn buscar(&self, id: u64) -> Option<&dyn Organismo> {
+        Poblacion::buscar(self, id)
+    }
+}
+
+// El diseño anterior a las tablas: una caja por individuo en un solo vector,
+// con las consultas tal como las hacía el simulador.
+struct Cajas(Vec<Box<dyn Organismo>>);
+
+impl Consultas for Cajas {
+    fn censo(&self) -> BTreeMap<String, usize> {
+        let mut censo = BTreeMap::new();
+        for org in &self.0 {
+            *censo.entry(org.nombre().to_string()).or_default() += 1;
+        }
+        censo
+    }
+
+    fn contar_especie(&self, especie: &str) -> usize {
+        self.0.iter().filter(|o| o.nombre() == especie).count()
+    }
+
+    fn buscar(&self, id: u64) -> Option<&dyn Organismo> {
+        self.0.iter().find(|o| o.id() == id).map(|o| o.as_ref())
+    }
+}
+
+// Mide `consultas` censos, conteos de cada especie y búsquedas por ID
+// repartidas entre todos los individuos.
+fn consultar(poblacion: &impl Consultas, ids: &[u64], consultas: usize) -> [Duration; 3] {
+    let paso = (ids.len() / consultas).max(1);
+
+    let inicio = Instant::now();
+    for _ in 0..consultas {
+        black_box(poblacion.censo());
+    }
+    let censo = inicio.elapsed();
+
+    let inicio = Instant::now();
+    for _ in 0..consultas {
+        for especie in ["Cabra", "Conejo", "Lobo"] {
+            black_box(poblacion.contar_especie(especie));
+        }
+    }
+    let conteos = inicio.elapsed();
+
+    let inicio = Instant::now();
+    for id in ids.iter().step_by(paso).take(consultas) {
+        black_box(poblacion.buscar(*id).expect("el ID salió de la población"));
+    }
+    [censo, conteos, inicio.elapsed()]
+}
+
+// Las mismas consultas sobre la misma población en los dos diseños;
+// devuelve los tiempos con cajas y con tablas.
+fn comparar(n: u32, consultas: usize) -> ([Duration; 3], [Duration; 3]) {
+    let sim = escenario(n, false).crear_simulador(None);
+    let ids: Vec<u64> = sim.poblacion.iter().map(|o| o.id()).collect();
+
+    let mut cajas: Vec<Box<dyn Organismo>> = Vec::with_capacity(sim.poblacion.len());
+    for tabla in &sim.poblacion.herbivoros {
+        for h in &tabla.individuos {
+            let mut copia = Herbivoro::new(tabla.especie.clone(), h.sexo);
+            copia.identidad = h.identidad;
+            cajas.push(Box::new(copia));
+        }
+    }
+    for lobo in &sim.poblacion.lobos {
+        cajas.push(Box::new(lobo.clone()));
+    }
+    // Antes las crías se agregaban al final: el vector quedaba en orden de ID, no por especie.
+    cajas.sort_by_key(|o| o.id());
+
+    (consultar(&Cajas(cajas), &ids, consultas), consultar(&sim.poblacion, &ids, consultas))
+}
+
+fn main() {
+    let mut tamaños = Vec::new();
+    let mut dias = 10;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--dias" => dias = args.next().and_then(|d| d.parse().ok()).expect("--dias necesita un número"),
+            "--bench" => {}
+            otro => tamaños.push(otro.parse::<u32>().expect("los tamaños son números")),
+        }
+    }
+    if tamaños.is_empty() {
+        tamaños = vec![10_000, 100_000, 1_000_000];
+    }
+
+    println!("{:>10} {:>9} {:>10} {:>12} {:>10}", "individuos", "espacio", "armado ms", "ms por día", "al final");
+    for &n in &tamaños {
+        for espacial in [false, true] {
+            let (armado, total, final_) = medir(n, espacial, dias);
+            println!(
+                "{:>10} {:>9} {:>10.1} {:>12.1} {:>10}",
+                n,
+                if espacial { "grilla" } else { "no" },
+                armado.as_secs_f64() * 1000.0,
+                total.as_secs_f64() * 1000.0 / dias as f64,
+                final_
+            );
+        }
+    }
+
+    println!("\nCien censos, cien conteos de cada especie y cien búsquedas por ID:");
+    println!("{:>10} {:>7} {:>12} {:>12} {:>12}", "individuos", "diseño", "censo ms", "conteos ms", "buscar ms");
+    for &n in &tamaños {
+        let (cajas, tablas) = comparar(n, 100);
+        for (diseño, tiempos) in [("cajas", cajas), ("tablas", tablas)] {
+            let [censo, conteos, buscar] = tiempos.map(|t| t.as_secs_f64() * 1000.0);
+            println!("{:>10} {:>7} {:>12.2} {:>12.2} {:>12.2}", n, diseño, censo, conteos, buscar);
+        }
+    }
+}
@@ -0,0 +1,280 @@
+//! Calibración de la curva de Gompertz con datos de peso por edad
+//! (mínimos cuadrados no lineales por Levenberg-Marquardt).
+
+use crate::edo::resolver;
+use crate::modelo::Gompertz;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Resultado del ajuste: los parámetros listos para `crecimiento` de una
+/// especie, sus errores estándar y la calidad del ajuste.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AjusteGompertz {
+    pub curva: Gompertz,
+    pub error_peso_maximo: f64,
+    pub error_tasa: f64,
+    pub error_inflexion: f64,
+    /// Coeficiente de determinación R².
+    pub r2: f64,
+    /// Raíz del error cuadrático medio (kg).
+    pub rmse: f64,
+    pub observaciones: usize,
+    pub iteraciones: u32,
+}
+
+impl AjusteGompertz {
+    /// Línea para pegar en la sección de la especie del escenario.
+    pub fn a_toml(&self) -> String {
+        format!(
+            "crecimiento = {{ gompertz = {{ peso_maximo = {:.4}, tasa = {:.6}, inflexion = {:.2} }} }}",
+            self.curva.peso_maximo, self.curva.tasa, self.curva.inflexion
+        )
+    }
+}
+
+impl fmt::Display for AjusteGompertz {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Ajuste de Gompertz con {} observaciones ({} iteraciones)", self.observaciones, self.iteraciones)?;
+        writeln!(f, "  A (peso máximo) = {:.4} ± {:.4}", self.curva.peso_maximo, self.error_peso_maximo)?;
+        writeln!(f, "  B (tasa)        = {:.6} ± {:.6}", self.curva.tasa, self.error_tasa)?;
+        writeln!(f, "  M (inflexión)   = {:.2} ± {:.2}", self.curva.inflexion, self.error_inflexion)?;
+        write!(f, "  R² = {:.4}, RMSE = {:.4} kg", self.r2, self.rmse)
+    }
+}
+
+#[derive(Debug)]
+pub enum ErrorAjuste {
+    Lectura(io::Error),
+    /// Una línea del CSV no es un par edad,peso.
+    Formato { linea: usize, motivo: String },
+    /// Hacen falta al menos cuatro pares para estimar tres parámetros y su error.
+    DatosInsuficientes(usize),
+    /// El método no llegó a una solución (datos sin forma sigmoidea, por ejemplo).
+    NoConverge,
+}
+
+impl fmt::Display for ErrorAjuste {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorAjuste::Lectura(e) => write!(f, "no se pudieron leer los datos: {}", e),
+            ErrorAjuste::Formato { linea, motivo } => write!(f, "línea {}: {}", linea, motivo),
+            ErrorAjuste::DatosInsuficientes(n) => write!(f, "se necesitan al menos 4 pares edad,peso (hay {})", n),
+            ErrorAjuste::NoConverge => write!(f, "el ajuste no convergió"),
+        }
+    }
+}
+
+impl std::error::Error for ErrorAjuste {}
+
+/// Lee pares edad,peso (edad en días, peso en kg) de un CSV. La primera línea
+/// puede ser un encabezado; se aceptan `,` o `;` y se ignoran las líneas vacías.
+pub fn leer_csv(texto: &str) -> Result<Vec<(f64, f64)>, ErrorAjuste> {
+    let mut datos = Vec::new();
+    let mut encabezado = false;
+    for (i, linea) in texto.lines().enumerate() {
+        let linea = linea.trim();
+        if linea.is_empty() || linea.starts_with('#') {
+            continue;
+        }
+        let campos: Vec<&str> = linea.split([',', ';']).map(str::trim).collect();
+        let numeros: Result<Vec<f64>, _> = campos.iter().take(2).map(|c| c.parse::<f64>()).collect();
+        match numeros {
+            Ok(n) if n.len() == 2 && n.iter().all(|v| v.is_finite()) => datos.push((n[0], n[1])),
+            // Encabezado: solo se tolera antes del primer dato.
+            Err(_) if datos.is_empty() && !encabezado => encabezado = true,
+            _ => {
+                return Err(ErrorAjuste::Formato { linea: i + 1, motivo: format!("se esperaba edad,peso y hay '{}'", linea) });
+            }
+        }
+    }
+    Ok(datos)
+}
+
+pub fn cargar_csv(ruta: impl AsRef<Path>) -> Result<Vec<(f64, f64)>, ErrorAjuste> {
+    leer_csv(&fs::read_to_string(ruta).map_err(ErrorAjuste::Lectura)?)
+}
+
+// Peso de la curva y sus derivadas respecto de A, B y M.
+fn evaluar(p: &[f64; 3], t: f64) -> (f64, [f64; 3]) {
+    let [a, b, m] = *p;
+    let e = (b * (m - t)).exp();
+    let g = (-e).exp();
+    let f = a * g;
+    (f, [g, -f * e * (m - t), -f * e * b])
+}
+
+fn suma_cuadrados(p: &[f64; 3], datos: &[(f64, f64)]) -> f64 {
+    datos.iter().map(|&(t, w)| (w - evaluar(p, t).0).powi(2)).sum()
+}
+
+// Punto de partida: A algo mayor que el peso máximo observado y B, M de la
+// recta ln(-ln(w/A)) = B·M - B·t.
+fn estimacion_inicial(datos: &[(f64, f64)]) -> [f64; 3] {
+    let a = datos.iter().map(|d| d.1).fold(0.0, f64::max) * 1.05;
+    let puntos: Vec<(f64, f64)> = datos.iter()
+        .filter(|&&(_, w)| w > 0.0 && w < a)
+        .map(|&(t, w)| (t, (-(w / a).ln()).ln()))
+        .collect();
+    let n = puntos.len() as f64;
+    let media_t = puntos.iter().map(|p| p.0).sum::<f64>() / n;
+    let media_y = puntos.iter().map(|p| p.1).sum::<f64>() / n;
+    let sxy: f64 = puntos.iter().map(|p| (p.0 - media_t) * (p.1 - media_y)).sum();
+    let sxx: f64 = puntos.iter().map(|p| (p.0 - media_t).powi(2)).sum();
+    let pendiente = sxy / sxx;
+    if n >= 2.0 && pendiente.is_finite() && pendiente < 0.0 {
+        let b = -pendiente;
+        [a, b, (media_y - pendiente * media_t) / b]
+    } else {
+        let edades = datos.iter().map(|d| d.0);
+        let (min, max) = edades.fold((f64::MAX, f64::MIN), |(lo, hi), t| (lo.min(t), hi.max(t)));
+        [a, 4.0 / (max - min).max(1.0), (min + max) / 2.0]
+    }
+}
+
+/// Ajusta A, B y M a los pares (edad, peso) por Levenberg-Marquardt.
+/// `inicial` permite partir de una curva conocida; si no, se estima de los datos.
+pub fn ajustar_gompertz(datos: &[(f64, f64)], inicial: Option<Gompertz>) -> Result<AjusteGompertz, ErrorAjuste> {
+    let n = datos.len();
+    if n < 4 {
+        return Err(ErrorAjuste::DatosInsuficientes(n));
+    }
+
+    let mut p = match inicial {
+        Some(g) => [g.peso_maximo, g.tasa, g.inflexion],
+        None => estimacion_inicial(datos),
+    };
+    let mut ssr = suma_cuadrados(&p, datos);
+    let mut lambda = 1e-3;
+    let mut iteraciones = 0;
+    let mut convergio = false;
+
+    while iteraciones < 500 {
+        iteraciones += 1;
+        // Ecuaciones normales JᵀJ·δ = Jᵀr.
+        let mut jtj = [[0.0; 3]; 3];
+        let mut jtr = [0.0; 3];
+        for &(t, w) in datos {
+            let (f, j) = evaluar(&p, t);
+            for i in 0..3 {
+                jtr[i] += j[i] * (w - f);
+                for k in 0..3 {
+                    jtj[i][k] += j[i] * j[k];
+                }
+            }
+        }
+
+        // Se amortigua hasta que el paso mejore el ajuste.
+        let mut mejoro = false;
+        while lambda < 1e12 {
+            let mut amortiguada = jtj;
+            for (i, fila) in amortiguada.iter_mut().enumerate() {
+                fila[i] *= 1.0 + lambda;
+            }
+            let Some(delta) = resolver(amortiguada, jtr) else {
+                lambda *= 10.0;
+                continue;
+            };
+            let nuevo = [p[0] + delta[0], p[1] + delta[1], p[2] + delta[2]];
+            let nuevo_ssr = suma_cuadrados(&nuevo, datos);
+            if nuevo_ssr.is_finite() && nuevo_ssr <= ssr {
+                let cambio = (0..3).map(|i| delta[i].abs() / p[i].abs().max(1e-12)).fold(0.0, f64::max);
+                convergio = cambio < 1e-10 || (ssr - nuevo_ssr) <= 1e-15 * ssr.max(1e-300);
+                p = nuevo;
+                ssr = nuevo_ssr;
+                lambda = (lambda / 10.0).max(1e-12);
+                mejoro = true;
+                break;
+            }
+            lambda *= 10.0;
+        }
+        if !mejoro {
+            // Ningún paso mejora: es el mínimo solo si el residuo ya es
+            // perpendicular a cada columna del jacobiano (gradiente nulo).
+            // Un parámetro que no mueve la curva (columna nula) no lo es.
+            convergio = ssr == 0.0 || (0..3).all(|i| jtr[i].abs() <= 1e-6 * (jtj[i][i] * ssr).sqrt());
+            break;
+        }
+        if convergio {
+            break;
+        }
+    }
+    if !convergio || !p.iter().all(|v| v.is_finite()) || p[0] <= 0.0 || p[1] <= 0.0 {
+        return Err(ErrorAjuste::NoConverge);
+    }
+
+    // Covarianza s²·(JᵀJ)⁻¹ en el óptimo.
+    let mut jtj = [[0.0; 3]; 3];
+    for &(t, _) in datos {
+        let (_, j) = evaluar(&p, t);
+        for i in 0..3 {
+            for k in 0..3 {
+                jtj[i][k] += j[i] * j[k];
+            }
+        }
+    }
+    let s2 = ssr / (n - 3) as f64;
+    let mut errores = [f64::NAN; 3];
+    for (i, error) in errores.iter_mut().enumerate() {
+        let mut unidad = [0.0; 3];
+        unidad[i] = 1.0;
+        if let Some(columna) = resolver(jtj, unidad) {
+            *error = (s2 * columna[i]).sqrt();
+        }
+    }
+
+    let media = datos.iter().map(|d| d.1).sum::<f64>() / n as f64;
+    let total: f64 = datos.iter().map(|d| (d.1 - media).powi(2)).sum();
+    Ok(AjusteGompertz {
+        curva: Gompertz { peso_maximo: p[0], tasa: p[1], inflexion: p[2] },
+        error_peso_maximo: errores[0],
+        error_tasa: errores[1],
+        error_inflexion: errores[2],
+        r2: if total > 0.0 { 1.0 - ssr / total } else { 1.0 },
+        rmse: (ssr / n as f64).sqrt(),
+        observaciones: n,
+        iteraciones,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modelo::CurvaCrecimiento;
+
+    const CURVA: Gompertz = Gompertz { peso_maximo: 60.0, tasa: 0.012, inflexion: 180.0 };
+
+    #[test]
+    fn recupera_la_curva_de_datos_sin_ruido() {
+        let datos: Vec<(f64, f64)> = (0..=40).map(|k| k as f64 * 20.0).map(|t| (t, CURVA.peso(t))).collect();
+        let ajuste = ajustar_gompertz(&datos, None).unwrap();
+        let c = ajuste.curva;
+        assert!((c.peso_maximo - CURVA.peso_maximo).abs() < 1e-6, "{:?}", c);
+        assert!((c.tasa - CURVA.tasa).abs() < 1e-9, "{:?}", c);
+        assert!((c.inflexion - CURVA.inflexion).abs() < 1e-5, "{:?}", c);
+        assert!(ajuste.r2 > 1.0 - 1e-12 && ajuste.rmse < 1e-6);
+    }
+
+    #[test]
+    fn lee_el_csv_con_encabezado_y_rechaza_lineas_sueltas() {
+        let datos = leer_csv("edad;peso\n\n0;2.5\n# comentario\n30, 8\n").unwrap();
+        assert_eq!(datos, vec![(0.0, 2.5), (30.0, 8.0)]);
+        assert!(matches!(leer_csv("0,2.5\nedad,peso\n"), Err(ErrorAjuste::Formato { linea: 2, .. })));
+    }
+
+    #[test]
+    fn no_da_por_convergido_un_ajuste_que_nunca_avanzo() {
+        // Con la inflexión tan atrás y la tasa tan alta la curva es plana en
+        // A: B y M no mueven nada y ningún paso mejora el ajuste.
+        let datos: Vec<(f64, f64)> = (0..=40).map(|k| k as f64 * 20.0).map(|t| (t, CURVA.peso(t))).collect();
+        let lejos = Gompertz { peso_maximo: 1e6, tasa: 50.0, inflexion: -1e4 };
+        assert!(matches!(ajustar_gompertz(&datos, Some(lejos)), Err(ErrorAjuste::NoConverge)));
+    }
+
+    #[test]
+    fn pide_al_menos_cuatro_pares() {
+        let datos = [(0.0, 1.0), (10.0, 2.0), (20.0, 3.0)];
+        assert!(matches!(ajustar_gompertz(&datos, None), Err(ErrorAjuste::DatosInsuficientes(3))));
+    }
+}
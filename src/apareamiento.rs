@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+/// Sistema de apareamiento de una especie: con quién se aparea cada
+/// hembra y cuántas hembras puede cubrir cada macho maduro en un día.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SistemaApareamiento {
+    /// Macho y hembra forman pareja la primera vez que se aparean y solo se
+    /// aparean entre sí, temporada tras temporada, hasta que uno muere.
+    #[default]
+    Monogamia,
+    /// Cada macho puede cubrir hasta `hembras_por_macho` hembras.
+    Poliginia { hembras_por_macho: u32 },
+}
+
+impl SistemaApareamiento {
+    /// Cuántas hembras puede cubrir un macho por día.
+    /// En monogamia, además, siempre la misma.
+    pub fn hembras_por_macho(&self) -> usize {
+        match self {
+            SistemaApareamiento::Monogamia => 1,
+            SistemaApareamiento::Poliginia { hembras_por_macho } => *hembras_por_macho as usize,
+        }
+    }
+}
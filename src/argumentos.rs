@@ -0,0 +1,348 @@
+use crate::escenario::{Escenario, ErrorEscenario};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::PathBuf;
+
+/// Texto de ayuda de los binarios `simulador` y `ecosistema_grafico`.
+pub const AYUDA: &str = "\
+Uso: simulador [opciones]
+     ecosistema_grafico [opciones]
+
+Opciones:
+  -e, --escenario RUTA       escenario TOML o JSON con la biología y la población inicial
+  -d, --dias N               días a simular (por defecto 300 o los del escenario)
+  -s, --semilla N            semilla del generador aleatorio (por defecto, al azar)
+  -p, --poblacion ESPECIE=N  individuos iniciales de sexo aleatorio de cualquier especie
+      --machos ESPECIE=N     machos iniciales de una especie
+      --hembras ESPECIE=N    hembras iniciales de una especie
+      --cabras N             cabras iniciales con sexo aleatorio (por defecto 100)
+      --cabras-machos N      cabras macho iniciales
+      --cabras-hembras N     cabras hembra iniciales
+      --conejos N            conejos iniciales con sexo aleatorio (por defecto 10)
+      --conejos-machos N     conejos macho iniciales
+      --conejos-hembras N    conejos hembra iniciales
+      --lobos N              lobos iniciales con sexo aleatorio (por defecto 1)
+      --lobos-machos N       lobos macho iniciales
+      --lobos-hembras N      lobos hembra iniciales
+      --cargar RUTA          sigue una corrida guardada (JSON o binario) en vez de
+                             arrancar el escenario; -d cuenta los días que se agregan.
+                             La semilla, la biología y la población vienen del archivo:
+                             no admite -s, -e, las opciones de población, --replicas,
+                             --barrido ni --media-campo
+      --guardar RUTA         guarda el estado completo al terminar (simulador) o al
+                             pulsar G (ecosistema_grafico); JSON si la ruta termina
+                             en .json y, si no, el mismo JSON comprimido con deflate
+                             (no es un formato binario estable)
+
+Las cantidades dadas reemplazan a las del escenario para esa especie.
+--cabras, --conejos y --lobos son atajos de --poblacion Cabra=N, etc.
+
+Solo para simulador:
+  -f, --formato FORMATO      texto, csv o json (por defecto texto)
+  -o, --salida RUTA          archivo de salida (por defecto, la consola)
+  -v, --detallado            reporte completo por individuo en vez del resumen diario
+      --grabar RUTA          graba la corrida día por día para verla después con
+                             ecosistema_grafico --reproducir (JSON si termina en .json)
+      --media-campo RUTA     escribe en CSV la predicción del modelo de Lotka-Volterra
+                             equivalente (dos primeras presas y lobo) para comparar
+      --respuesta RUTA       escribe en CSV, día por día, los ataques y cazas por lobo
+                             junto a los que predice la respuesta funcional del escenario
+                             para las presas y lobos que cada uno tenía a su alcance
+      --ajustar-gompertz RUTA
+                             ajusta la curva de Gompertz a un CSV de pares edad,peso,
+                             muestra A, B, M con sus errores y termina sin simular
+      --replicas N           corre N réplicas en paralelo con semillas derivadas de -s
+                             y escribe en CSV media, mediana, percentiles 5/25/75/95
+                             y probabilidad de extinción por día y especie
+      --extinciones RUTA     con --replicas, escribe en CSV el día de extinción
+                             de cada especie en cada réplica
+      --barrido RUTA         barrido de parámetros descrito en TOML (grilla, hipercubo
+                             latino, Sobol, Morris o índices de Sobol); escribe en CSV
+                             los parámetros y las métricas de cada punto
+      --sensibilidad RUTA    con --barrido de Morris o índices de Sobol, escribe en CSV
+                             la influencia de cada parámetro sobre cada métrica
+
+Solo para ecosistema_grafico:
+      --reproducir RUTA      reproduce una corrida grabada en vez de simular
+                             (Space pausa, ←/→ un día, Inicio/Fin, ↑/↓ velocidad,
+                             clic en la barra para saltar)
+
+  -h, --ayuda                muestra esta ayuda
+";
+
+/// Qué se escribe en la salida.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Formato {
+    /// Bitácora legible día por día.
+    Texto,
+    /// Historial de la población en CSV.
+    Csv,
+    /// Historial de la población en JSON.
+    Json,
+}
+
+/// Cuánto detalle tiene la bitácora de texto.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Detalle {
+    /// Una línea por día con los conteos por especie.
+    Resumen,
+    /// Todos los eventos y el listado de cada individuo.
+    Completo,
+}
+
+/// Población inicial de una especie: individuos de sexo aleatorio más
+/// machos y hembras pedidos explícitamente.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PoblacionInicial {
+    pub aleatorios: u32,
+    pub machos: u32,
+    pub hembras: u32,
+}
+
+impl PoblacionInicial {
+    pub fn total(&self) -> u32 {
+        self.aleatorios + self.machos + self.hembras
+    }
+}
+
+/// Opciones de la línea de comandos. Lo que no se da queda en `None`
+/// y se toma del escenario (o de sus valores por defecto).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Argumentos {
+    pub escenario: Option<PathBuf>,
+    pub dias: Option<u32>,
+    pub semilla: Option<u64>,
+    /// Población inicial por nombre de especie.
+    pub poblacion: BTreeMap<String, PoblacionInicial>,
+    pub formato: Formato,
+    pub salida: Option<PathBuf>,
+    pub detalle: Detalle,
+    /// Dónde escribir la trayectoria del modelo determinista, si se pide.
+    pub media_campo: Option<PathBuf>,
+    /// Dónde escribir la caza observada frente a la respuesta funcional del lobo.
+    pub respuesta: Option<PathBuf>,
+    /// CSV de edad,peso para calibrar una curva de crecimiento en vez de simular.
+    pub ajustar_gompertz: Option<PathBuf>,
+    /// Réplicas de Monte Carlo en vez de una corrida única.
+    pub replicas: Option<u32>,
+    pub extinciones: Option<PathBuf>,
+    /// Archivo TOML con un barrido de parámetros.
+    pub barrido: Option<PathBuf>,
+    pub sensibilidad: Option<PathBuf>,
+    /// Estado guardado desde el que se continúa, y dónde guardar el final.
+    pub cargar: Option<PathBuf>,
+    pub guardar: Option<PathBuf>,
+    /// Grabación de la corrida para ver en la interfaz, y la que se reproduce.
+    pub grabar: Option<PathBuf>,
+    pub reproducir: Option<PathBuf>,
+}
+
+impl Default for Argumentos {
+    fn default() -> Self {
+        Argumentos {
+            escenario: None,
+            dias: None,
+            semilla: None,
+            poblacion: BTreeMap::new(),
+            formato: Formato::Texto,
+            salida: None,
+            detalle: Detalle::Resumen,
+            media_campo: None,
+            respuesta: None,
+            ajustar_gompertz: None,
+            replicas: None,
+            extinciones: None,
+            barrido: None,
+            sensibilidad: None,
+            cargar: None,
+            guardar: None,
+            grabar: None,
+            reproducir: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorArgumentos {
+    /// Se pidió la ayuda (no es un error real, pero corta el análisis).
+    Ayuda,
+    OpcionDesconocida(String),
+    FaltaValor(String),
+    ValorInvalido { opcion: String, valor: String, esperado: &'static str },
+    /// Dos opciones que no tienen sentido juntas.
+    Incompatibles { opcion: &'static str, con: &'static str },
+}
+
+impl fmt::Display for ErrorArgumentos {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorArgumentos::Ayuda => write!(f, "se pidió la ayuda"),
+            ErrorArgumentos::OpcionDesconocida(opcion) => write!(f, "opción desconocida '{}'", opcion),
+            ErrorArgumentos::FaltaValor(opcion) => write!(f, "falta el valor de '{}'", opcion),
+            ErrorArgumentos::ValorInvalido { opcion, valor, esperado } => {
+                write!(f, "valor inválido '{}' para '{}' (se esperaba {})", valor, opcion, esperado)
+            }
+            ErrorArgumentos::Incompatibles { opcion, con } => write!(f, "'{}' no se puede usar con {}", opcion, con),
+        }
+    }
+}
+
+impl std::error::Error for ErrorArgumentos {}
+
+impl Argumentos {
+    /// Interpreta los argumentos de la línea de comandos (sin el nombre del programa).
+    pub fn desde<I: IntoIterator<Item = String>>(args: I) -> Result<Self, ErrorArgumentos> {
+        let mut resultado = Argumentos::default();
+
+        let mut args = args.into_iter();
+        while let Some(opcion) = args.next() {
+            // Acepta tanto `--opcion valor` como `--opcion=valor`.
+            let (opcion, en_linea) = match opcion.split_once('=') {
+                Some((o, v)) if o.starts_with("--") => (o.to_string(), Some(v.to_string())),
+                _ => (opcion, None),
+            };
+            let mut valor = || -> Result<String, ErrorArgumentos> {
+                en_linea.clone().or_else(|| args.next()).ok_or_else(|| ErrorArgumentos::FaltaValor(opcion.clone()))
+            };
+
+            match opcion.as_str() {
+                "-h" | "--ayuda" => return Err(ErrorArgumentos::Ayuda),
+                "-v" | "--detallado" => resultado.detalle = Detalle::Completo,
+                "-e" | "--escenario" => resultado.escenario = Some(PathBuf::from(valor()?)),
+                "-d" | "--dias" => {
+                    let dias = numero(&opcion, &valor()?)?;
+                    if dias == 0 {
+                        return Err(invalido(&opcion, "0", "un número de días mayor que 0"));
+                    }
+                    resultado.dias = Some(dias);
+                }
+                "-s" | "--semilla" => resultado.semilla = Some(numero(&opcion, &valor()?)?),
+                "-f" | "--formato" => {
+                    let v = valor()?;
+                    resultado.formato = match v.as_str() {
+                        "texto" => Formato::Texto,
+                        "csv" => Formato::Csv,
+                        "json" => Formato::Json,
+                        _ => return Err(invalido(&opcion, &v, "texto, csv o json")),
+                    };
+                }
+                "-o" | "--salida" => resultado.salida = Some(PathBuf::from(valor()?)),
+                "--media-campo" => resultado.media_campo = Some(PathBuf::from(valor()?)),
+                "--respuesta" => resultado.respuesta = Some(PathBuf::from(valor()?)),
+                "--ajustar-gompertz" => resultado.ajustar_gompertz = Some(PathBuf::from(valor()?)),
+                "--replicas" => {
+                    let replicas = numero(&opcion, &valor()?)?;
+                    if replicas == 0 {
+                        return Err(invalido(&opcion, "0", "un número de réplicas mayor que 0"));
+                    }
+                    resultado.replicas = Some(replicas);
+                }
+                "--extinciones" => resultado.extinciones = Some(PathBuf::from(valor()?)),
+                "--barrido" => resultado.barrido = Some(PathBuf::from(valor()?)),
+                "--sensibilidad" => resultado.sensibilidad = Some(PathBuf::from(valor()?)),
+                "--cargar" => resultado.cargar = Some(PathBuf::from(valor()?)),
+                "--guardar" => resultado.guardar = Some(PathBuf::from(valor()?)),
+                "--grabar" => resultado.grabar = Some(PathBuf::from(valor()?)),
+                "--reproducir" => resultado.reproducir = Some(PathBuf::from(valor()?)),
+                "-p" | "--poblacion" => {
+                    let (especie, n) = especie_y_numero(&opcion, &valor()?)?;
+                    resultado.poblacion_de(especie).aleatorios = n;
+                }
+                "--machos" => {
+                    let (especie, n) = especie_y_numero(&opcion, &valor()?)?;
+                    resultado.poblacion_de(especie).machos = n;
+                }
+                "--hembras" => {
+                    let (especie, n) = especie_y_numero(&opcion, &valor()?)?;
+                    resultado.poblacion_de(especie).hembras = n;
+                }
+                _ => {
+                    // Atajos de las especies del escenario por defecto: --cabras, --lobos-machos...
+                    let Some((especie, sexo)) = atajo(&opcion) else {
+                        return Err(ErrorArgumentos::OpcionDesconocida(opcion));
+                    };
+                    let n = numero(&opcion, &valor()?)?;
+                    let poblacion = resultado.poblacion_de(especie.to_string());
+                    match sexo {
+                        "" => poblacion.aleatorios = n,
+                        "-machos" => poblacion.machos = n,
+                        _ => poblacion.hembras = n,
+                    }
+                }
+            }
+        }
+
+        // Una corrida cargada sigue con su propio generador, su biología y su
+        // población; lo que arranca corridas nuevas desde un escenario la descartaría.
+        if resultado.cargar.is_some() {
+            let incompatibles = [
+                (resultado.semilla.is_some(), "-s/--semilla"),
+                (!resultado.poblacion.is_empty(), "las opciones de población (-p, --machos, --hembras, --cabras...)"),
+                (resultado.escenario.is_some(), "-e/--escenario"),
+                (resultado.replicas.is_some(), "--replicas"),
+                (resultado.barrido.is_some(), "--barrido"),
+                (resultado.media_campo.is_some(), "--media-campo"),
+            ];
+            if let Some(&(_, con)) = incompatibles.iter().find(|(dada, _)| *dada) {
+                return Err(ErrorArgumentos::Incompatibles { opcion: "--cargar", con });
+            }
+        }
+        Ok(resultado)
+    }
+
+    /// Aplica al escenario lo que se haya dado por línea de comandos.
+    pub fn aplicar(&self, escenario: &mut Escenario) {
+        if let Some(dias) = self.dias { escenario.dias = Some(dias); }
+        if let Some(semilla) = self.semilla { escenario.semilla = Some(semilla); }
+        for (especie, cantidad) in &self.poblacion {
+            escenario.poblacion.insert(especie.clone(), *cantidad);
+        }
+    }
+
+    /// Carga el escenario pedido (o el por defecto) y le aplica las opciones.
+    pub fn escenario(&self) -> Result<Escenario, ErrorEscenario> {
+        let mut escenario = match &self.escenario {
+            Some(ruta) => Escenario::cargar(ruta)?,
+            None => Escenario::default(),
+        };
+        self.aplicar(&mut escenario);
+        escenario.validar()?;
+        Ok(escenario)
+    }
+
+    fn poblacion_de(&mut self, especie: String) -> &mut PoblacionInicial {
+        self.poblacion.entry(especie).or_default()
+    }
+}
+
+// `--cabras-machos` -> ("Cabra", "-machos"); `--lobos` -> ("Lobo", "").
+fn atajo(opcion: &str) -> Option<(&'static str, &str)> {
+    let resto = opcion.strip_prefix("--")?;
+    for (plural, especie) in [("cabras", "Cabra"), ("conejos", "Conejo"), ("lobos", "Lobo")] {
+        if let Some(sexo) = resto.strip_prefix(plural)
+            && matches!(sexo, "" | "-machos" | "-hembras")
+        {
+            return Some((especie, sexo));
+        }
+    }
+    None
+}
+
+// `Cabra=100` -> ("Cabra", 100).
+fn especie_y_numero(opcion: &str, valor: &str) -> Result<(String, u32), ErrorArgumentos> {
+    match valor.split_once('=') {
+        Some((especie, n)) if !especie.trim().is_empty() => Ok((especie.trim().to_string(), numero(opcion, n)?)),
+        _ => Err(invalido(opcion, valor, "ESPECIE=N")),
+    }
+}
+
+fn invalido(opcion: &str, valor: &str, esperado: &'static str) -> ErrorArgumentos {
+    ErrorArgumentos::ValorInvalido { opcion: opcion.to_string(), valor: valor.to_string(), esperado }
+}
+
+// Entero sin signo, con un mensaje claro si no lo es.
+fn numero<T: std::str::FromStr>(opcion: &str, valor: &str) -> Result<T, ErrorArgumentos> {
+    valor.parse().map_err(|_| invalido(opcion, valor, "un entero sin signo"))
+}
@@ -0,0 +1,592 @@
+use crate::escenario::{ErrorEscenario, Escenario};
+use crate::montecarlo::{self, Replica};
+use crate::organismo::Generador;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+/// Un parámetro del escenario que se hace variar entre `minimo` y `maximo`.
+/// La ruta empieza por el nombre de una especie herbívora, `Lobo` o una
+/// sección del escenario, y sigue por los campos: `Conejo.probabilidad_parto`,
+/// `Lobo.reserva_minima`, `Cabra.crecimiento.gompertz.tasa`,
+/// `vegetacion.tasa_rebrote` o `poblacion.Lobo.aleatorios`.
+/// Los campos enteros se redondean.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Parametro {
+    pub ruta: String,
+    pub minimo: f64,
+    pub maximo: f64,
+}
+
+/// Cómo se eligen los puntos del espacio de parámetros.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Muestreo {
+    /// Todas las combinaciones de `niveles` valores equiespaciados por parámetro.
+    Grilla { niveles: u32 },
+    /// Hipercubo latino: cada parámetro cubre sus `puntos` estratos una vez.
+    Hipercubo { puntos: u32 },
+    /// Secuencia cuasi aleatoria de Sobol (hasta 16 parámetros).
+    Sobol { puntos: u32 },
+    /// Trayectorias de Morris; da los efectos elementales de cada parámetro.
+    Morris { trayectorias: u32, niveles: u32 },
+    /// Diseño de Saltelli; da los índices de Sobol de primer orden y totales
+    /// (hasta 8 parámetros). Corre `puntos · (parámetros + 2)` escenarios.
+    IndicesSobol { puntos: u32 },
+}
+
+/// Barrido de parámetros sobre un escenario base, tal como se escribe en TOML:
+///
+/// ```toml
+/// replicas = 4
+/// muestreo = { morris = { trayectorias = 10, niveles = 4 } }
+///
+/// [[parametros]]
+/// ruta = "Conejo.probabilidad_parto"
+/// minimo = 0.05
+/// maximo = 0.2
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Barrido {
+    /// Corridas por punto; las métricas son el promedio. Todos los puntos usan
+    /// las mismas semillas, así las diferencias vienen de los parámetros.
+    #[serde(default = "una_replica")]
+    pub replicas: u32,
+    pub muestreo: Muestreo,
+    pub parametros: Vec<Parametro>,
+}
+
+fn una_replica() -> u32 {
+    1
+}
+
+// Dimensiones de la tabla de números de dirección de Sobol.
+const DIMENSIONES_SOBOL: usize = 16;
+
+// Métricas que se miden de cada especie, en el orden de las columnas.
+const METRICAS: [&str; 4] = ["final", "extinta", "media", "amplitud"];
+
+impl Barrido {
+    pub fn cargar(ruta: impl AsRef<Path>) -> Result<Self, ErrorEscenario> {
+        let texto = fs::read_to_string(ruta).map_err(ErrorEscenario::Lectura)?;
+        Self::desde_toml(&texto)
+    }
+
+    pub fn desde_toml(texto: &str) -> Result<Self, ErrorEscenario> {
+        let barrido: Barrido = toml::from_str(texto).map_err(|e| ErrorEscenario::Formato(e.to_string()))?;
+        barrido.validar()?;
+        Ok(barrido)
+    }
+
+    pub fn validar(&self) -> Result<(), ErrorEscenario> {
+        let invalido = |campo: &str, motivo| Err(ErrorEscenario::Invalido { campo: campo.to_string(), motivo });
+        let k = self.parametros.len();
+        if k == 0 {
+            return invalido("parametros", "debe tener al menos un parámetro");
+        }
+        if self.replicas == 0 {
+            return invalido("replicas", "debe ser al menos 1");
+        }
+        let mut rutas = BTreeSet::new();
+        for p in &self.parametros {
+            if !rutas.insert(p.ruta.as_str()) {
+                return invalido(&p.ruta, "está repetido");
+            }
+            if !(p.minimo.is_finite() && p.maximo.is_finite() && p.minimo <= p.maximo) {
+                return invalido(&p.ruta, "necesita minimo <= maximo, ambos finitos");
+            }
+        }
+        match self.muestreo {
+            Muestreo::Grilla { niveles: 0 }
+            | Muestreo::Hipercubo { puntos: 0 }
+            | Muestreo::Sobol { puntos: 0 }
+            | Muestreo::Morris { trayectorias: 0, .. }
+            | Muestreo::IndicesSobol { puntos: 0 } => invalido("muestreo", "necesita al menos un punto"),
+            Muestreo::Morris { niveles, .. } if niveles < 2 => invalido("muestreo.morris.niveles", "debe ser al menos 2"),
+            Muestreo::Sobol { .. } if k > DIMENSIONES_SOBOL => {
+                invalido("parametros", "admite hasta 16 parámetros con muestreo de Sobol")
+            }
+            Muestreo::IndicesSobol { .. } if 2 * k > DIMENSIONES_SOBOL => {
+                invalido("parametros", "admite hasta 8 parámetros para los índices de Sobol")
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Corre el escenario en cada punto del muestreo (en paralelo) y mide
+    /// las métricas de cada especie. `semilla` fija tanto el diseño como
+    /// las corridas.
+    pub fn ejecutar(&self, escenario: &Escenario, dias: u32, semilla: u64) -> Result<ResultadoBarrido, ErrorEscenario> {
+        let mut rng = Generador::seed_from_u64(semilla);
+        let k = self.parametros.len();
+        let unitarios = match self.muestreo {
+            Muestreo::Grilla { niveles } => grilla(k, niveles),
+            Muestreo::Hipercubo { puntos } => hipercubo(k, puntos, &mut rng),
+            Muestreo::Sobol { puntos } => sobol(k, puntos),
+            Muestreo::Morris { trayectorias, niveles } => morris(k, trayectorias, niveles, &mut rng),
+            Muestreo::IndicesSobol { puntos } => saltelli(k, puntos),
+        };
+
+        // Todos los escenarios se arman y validan antes de simular.
+        let base = serde_json::to_value(escenario).map_err(|e| ErrorEscenario::Formato(e.to_string()))?;
+        let puntos: Vec<Vec<f64>> = unitarios.iter()
+            .map(|u| u.iter().zip(&self.parametros).map(|(x, p)| p.minimo + x * (p.maximo - p.minimo)).collect())
+            .collect();
+        let escenarios = puntos.iter()
+            .map(|valores| self.escenario_en(&base, valores))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let semillas: Vec<u64> = (0..self.replicas).map(|r| montecarlo::semilla_replica(semilla, r)).collect();
+        let salidas: Vec<Vec<f64>> = escenarios.par_iter()
+            .map(|esc| {
+                let replicas: Vec<Replica> = semillas.iter().map(|&s| montecarlo::correr_replica(esc, s, dias)).collect();
+                medir(&replicas)
+            })
+            .collect();
+
+        // Las especies en orden alfabético, como en las réplicas.
+        let especies: BTreeSet<&str> = escenario.herbivoros.iter().map(|h| h.nombre.as_str())
+            .chain(std::iter::once("Lobo"))
+            .collect();
+        let metricas: Vec<String> = especies.iter()
+            .flat_map(|e| METRICAS.iter().map(move |m| format!("{}.{}", e, m)))
+            .collect();
+        let parametros: Vec<String> = self.parametros.iter().map(|p| p.ruta.clone()).collect();
+
+        let sensibilidad = match self.muestreo {
+            Muestreo::Morris { .. } => Some(efectos_morris(&unitarios, &salidas, &parametros, &metricas)),
+            Muestreo::IndicesSobol { puntos } => Some(indices_sobol(&salidas, puntos as usize, &parametros, &metricas)),
+            _ => None,
+        };
+
+        Ok(ResultadoBarrido { parametros, metricas, puntos, salidas, sensibilidad })
+    }
+
+    // El escenario base con los parámetros del barrido fijados en `valores`.
+    fn escenario_en(&self, base: &Value, valores: &[f64]) -> Result<Escenario, ErrorEscenario> {
+        let mut valor = base.clone();
+        for (p, &x) in self.parametros.iter().zip(valores) {
+            fijar(&mut valor, &p.ruta, x)
+                .map_err(|motivo| ErrorEscenario::Invalido { campo: p.ruta.clone(), motivo })?;
+        }
+        let escenario: Escenario = serde_json::from_value(valor).map_err(|e| ErrorEscenario::Formato(e.to_string()))?;
+        escenario.validar()?;
+        Ok(escenario)
+    }
+}
+
+// Escribe `x` en el campo numérico que indica la ruta. El primer tramo puede
+// ser el nombre de una especie herbívora o `Lobo`.
+fn fijar(escenario: &mut Value, ruta: &str, x: f64) -> Result<(), &'static str> {
+    let mut tramos = ruta.split('.');
+    let primero = tramos.next().unwrap_or_default();
+    let mut actual = if primero == "Lobo" {
+        escenario.get_mut("lobo")
+    } else if let Some(i) = escenario["herbivoros"].as_array()
+        .and_then(|hs| hs.iter().position(|h| h["nombre"] == primero))
+    {
+        escenario["herbivoros"].get_mut(i)
+    } else {
+        escenario.get_mut(primero)
+    };
+    for tramo in tramos {
+        actual = actual.and_then(|v| v.get_mut(tramo));
+    }
+    match actual {
+        Some(v) if v.is_u64() => *v = Value::from(x.round().max(0.0) as u64),
+        Some(v) if v.is_number() => *v = Value::from(x),
+        Some(_) => return Err("no es un campo numérico"),
+        None => return Err("no existe en el escenario"),
+    }
+    Ok(())
+}
+
+// Métricas de una corrida promediadas entre réplicas, especie por especie:
+// individuos al final, si se extinguió (0 o 1), y media y amplitud
+// ((máximo - mínimo) / 2) de la segunda mitad de la corrida, ya sin el transitorio.
+fn medir(replicas: &[Replica]) -> Vec<f64> {
+    let n = replicas.len() as f64;
+    let especies = replicas[0].conteos.keys();
+    especies
+        .flat_map(|especie| {
+            let mut suma = [0.0; METRICAS.len()];
+            for replica in replicas {
+                let serie = &replica.conteos[especie];
+                let segunda = &serie[serie.len() / 2..];
+                let max = segunda.iter().max().copied().unwrap_or(0) as f64;
+                let min = segunda.iter().min().copied().unwrap_or(0) as f64;
+                let valores = [
+                    *serie.last().unwrap_or(&0) as f64,
+                    if replica.extincion[especie].is_some() { 1.0 } else { 0.0 },
+                    segunda.iter().sum::<usize>() as f64 / segunda.len() as f64,
+                    (max - min) / 2.0,
+                ];
+                for (s, v) in suma.iter_mut().zip(valores) {
+                    *s += v;
+                }
+            }
+            suma.map(|s| s / n)
+        })
+        .collect()
+}
+
+/// Resultado del barrido: los valores de los parámetros y las métricas en cada punto.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResultadoBarrido {
+    pub parametros: Vec<String>,
+    /// Nombres de las métricas, `Especie.metrica`.
+    pub metricas: Vec<String>,
+    pub puntos: Vec<Vec<f64>>,
+    pub salidas: Vec<Vec<f64>>,
+    pub sensibilidad: Option<Sensibilidad>,
+}
+
+impl ResultadoBarrido {
+    /// Una fila por punto: número de punto, parámetros y métricas.
+    pub fn a_csv(&self) -> String {
+        let mut csv = String::from("punto");
+        for nombre in self.parametros.iter().chain(&self.metricas) {
+            let _ = write!(csv, ",{}", nombre);
+        }
+        csv.push('\n');
+        for (i, (punto, salida)) in self.puntos.iter().zip(&self.salidas).enumerate() {
+            let _ = write!(csv, "{}", i);
+            for v in punto.iter().chain(salida) {
+                let _ = write!(csv, ",{}", v);
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+/// Sensibilidad de una métrica a un parámetro.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Efecto {
+    pub metrica: String,
+    pub parametro: String,
+    /// Morris: μ, μ* y σ de los efectos elementales, en unidades de la
+    /// métrica por recorrido completo del parámetro.
+    /// Sobol: índice de primer orden, total y 0.
+    pub valores: [f64; 3],
+}
+
+/// Análisis de sensibilidad global, según el muestreo.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Sensibilidad {
+    Morris(Vec<Efecto>),
+    Sobol(Vec<Efecto>),
+}
+
+impl Sensibilidad {
+    pub fn efectos(&self) -> &[Efecto] {
+        match self {
+            Sensibilidad::Morris(e) | Sensibilidad::Sobol(e) => e,
+        }
+    }
+
+    /// Parámetros ordenados de más a menos influyentes sobre una métrica
+    /// (por μ* en Morris y por índice total en Sobol).
+    pub fn ranking(&self, metrica: &str) -> Vec<(&str, f64)> {
+        let mut ranking: Vec<(&str, f64)> = self.efectos().iter()
+            .filter(|e| e.metrica == metrica)
+            .map(|e| (e.parametro.as_str(), e.valores[1]))
+            .collect();
+        ranking.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranking
+    }
+
+    pub fn a_csv(&self) -> String {
+        let mut csv = match self {
+            Sensibilidad::Morris(_) => String::from("metrica,parametro,mu,mu_estrella,sigma\n"),
+            Sensibilidad::Sobol(_) => String::from("metrica,parametro,primer_orden,total\n"),
+        };
+        for e in self.efectos() {
+            let [a, b, c] = e.valores;
+            let _ = match self {
+                Sensibilidad::Morris(_) => writeln!(csv, "{},{},{:.6},{:.6},{:.6}", e.metrica, e.parametro, a, b, c),
+                Sensibilidad::Sobol(_) => writeln!(csv, "{},{},{:.6},{:.6}", e.metrica, e.parametro, a, b),
+            };
+        }
+        csv
+    }
+}
+
+// Todas las combinaciones de `niveles` valores en [0, 1]; con un solo nivel, el centro.
+fn grilla(k: usize, niveles: u32) -> Vec<Vec<f64>> {
+    let nivel = |i: u32| if niveles == 1 { 0.5 } else { i as f64 / (niveles - 1) as f64 };
+    let total = (niveles as usize).pow(k as u32);
+    (0..total)
+        .map(|mut n| {
+            let mut punto = vec![0.0; k];
+            for x in punto.iter_mut().rev() {
+                *x = nivel((n % niveles as usize) as u32);
+                n /= niveles as usize;
+            }
+            punto
+        })
+        .collect()
+}
+
+fn hipercubo(k: usize, puntos: u32, rng: &mut Generador) -> Vec<Vec<f64>> {
+    let n = puntos as usize;
+    let mut puntos = vec![vec![0.0; k]; n];
+    for j in 0..k {
+        let mut estratos: Vec<usize> = (0..n).collect();
+        estratos.shuffle(rng);
+        for (punto, estrato) in puntos.iter_mut().zip(estratos) {
+            punto[j] = (estrato as f64 + rng.random::<f64>()) / n as f64;
+        }
+    }
+    puntos
+}
+
+// Números de dirección de Joe y Kuo para las dimensiones 2 a 16:
+// grado s del polinomio primitivo, sus coeficientes a y los m iniciales.
+const DIRECCIONES: [(u32, u32, &[u32]); DIMENSIONES_SOBOL - 1] = [
+    (1, 0, &[1]),
+    (2, 1, &[1, 3]),
+    (3, 1, &[1, 3, 1]),
+    (3, 2, &[1, 1, 1]),
+    (4, 1, &[1, 1, 3, 3]),
+    (4, 4, &[1, 3, 5, 13]),
+    (5, 2, &[1, 1, 5, 5, 17]),
+    (5, 4, &[1, 1, 5, 5, 5]),
+    (5, 7, &[1, 1, 7, 11, 19]),
+    (5, 11, &[1, 1, 5, 1, 1]),
+    (5, 13, &[1, 1, 1, 3, 11]),
+    (5, 14, &[1, 3, 5, 5, 31]),
+    (6, 1, &[1, 3, 3, 9, 7, 49]),
+    (6, 13, &[1, 1, 1, 15, 21, 21]),
+    (6, 16, &[1, 3, 1, 13, 27, 49]),
+];
+
+const BITS: usize = 32;
+
+// Los 32 números de dirección de una dimensión (la primera es van der Corput).
+fn direcciones(dimension: usize) -> [u32; BITS] {
+    let mut v = [0u32; BITS];
+    if dimension == 0 {
+        for (i, x) in v.iter_mut().enumerate() {
+            *x = 1 << (BITS - 1 - i);
+        }
+        return v;
+    }
+    let (s, a, m) = DIRECCIONES[dimension - 1];
+    let s = s as usize;
+    for i in 0..BITS {
+        v[i] = if i < s {
+            m[i] << (BITS - 1 - i)
+        } else {
+            let mut x = v[i - s] ^ (v[i - s] >> s);
+            for l in 1..s {
+                if (a >> (s - 1 - l)) & 1 == 1 {
+                    x ^= v[i - l];
+                }
+            }
+            x
+        };
+    }
+    v
+}
+
+// Los primeros `puntos` de la secuencia de Sobol en `k` dimensiones, sin el origen
+// (código de Gray: cada punto cambia una sola dirección respecto del anterior).
+fn sobol(k: usize, puntos: u32) -> Vec<Vec<f64>> {
+    let v: Vec<[u32; BITS]> = (0..k).map(direcciones).collect();
+    let mut x = vec![0u32; k];
+    (0..puntos)
+        .map(|i| {
+            let c = i.trailing_ones() as usize;
+            for (xj, vj) in x.iter_mut().zip(&v) {
+                *xj ^= vj[c];
+            }
+            x.iter().map(|&xj| xj as f64 / 2f64.powi(BITS as i32)).collect()
+        })
+        .collect()
+}
+
+// Trayectorias de Morris: cada una tiene k + 1 puntos sobre una grilla de
+// `niveles` valores y mueve un parámetro por vez, en orden aleatorio, un salto Δ.
+fn morris(k: usize, trayectorias: u32, niveles: u32, rng: &mut Generador) -> Vec<Vec<f64>> {
+    let paso = 1.0 / (niveles - 1) as f64;
+    // Δ = p / (2(p - 1)): con niveles pares, todos los niveles se visitan igual de seguido.
+    let delta = niveles as f64 / (2.0 * (niveles - 1) as f64);
+    // Niveles desde los que se puede subir Δ sin salirse de [0, 1]. Con
+    // niveles impares Δ no cae en la grilla, así que se redondea hacia abajo.
+    let bases = ((1.0 - delta) / paso + 1e-9).floor() as u32;
+    let mut puntos = Vec::with_capacity(trayectorias as usize * (k + 1));
+    for _ in 0..trayectorias {
+        let mut x: Vec<f64> = (0..k)
+            .map(|_| {
+                let base = rng.random_range(0..=bases) as f64 * paso;
+                if rng.random_bool(0.5) { base } else { base + delta }
+            })
+            .collect();
+        let mut orden: Vec<usize> = (0..k).collect();
+        orden.shuffle(rng);
+        puntos.push(x.clone());
+        for j in orden {
+            x[j] = if x[j] + delta <= 1.0 + 1e-12 { x[j] + delta } else { x[j] - delta };
+            puntos.push(x.clone());
+        }
+    }
+    puntos
+}
+
+// Efecto elemental de cada salto de cada trayectoria: cambio de la métrica
+// dividido por el salto del parámetro (en escala [0, 1]).
+fn efectos_morris(unitarios: &[Vec<f64>], salidas: &[Vec<f64>], parametros: &[String], metricas: &[String]) -> Sensibilidad {
+    let k = parametros.len();
+    // efectos[j][m]: efectos elementales del parámetro j sobre la métrica m.
+    let mut efectos = vec![vec![Vec::new(); metricas.len()]; k];
+    for trayectoria in (0..unitarios.len()).step_by(k + 1) {
+        for antes in trayectoria..trayectoria + k {
+            let despues = antes + 1;
+            let Some(j) = (0..k).find(|&j| unitarios[antes][j] != unitarios[despues][j]) else { continue };
+            let dx = unitarios[despues][j] - unitarios[antes][j];
+            for (m, lista) in efectos[j].iter_mut().enumerate() {
+                lista.push((salidas[despues][m] - salidas[antes][m]) / dx);
+            }
+        }
+    }
+
+    let mut resultado = Vec::new();
+    for (m, metrica) in metricas.iter().enumerate() {
+        for (parametro, por_metrica) in parametros.iter().zip(&efectos) {
+            let ee = &por_metrica[m];
+            let n = ee.len() as f64;
+            let mu = ee.iter().sum::<f64>() / n;
+            let mu_estrella = ee.iter().map(|e| e.abs()).sum::<f64>() / n;
+            let sigma = if ee.len() > 1 {
+                (ee.iter().map(|e| (e - mu).powi(2)).sum::<f64>() / (n - 1.0)).sqrt()
+            } else {
+                0.0
+            };
+            resultado.push(Efecto {
+                metrica: metrica.clone(),
+                parametro: parametro.clone(),
+                valores: [mu, mu_estrella, sigma],
+            });
+        }
+    }
+    Sensibilidad::Morris(resultado)
+}
+
+// Diseño de Saltelli a partir de una secuencia de Sobol de 2k dimensiones:
+// las matrices A y B (primeras y últimas k columnas) y, para cada parámetro i,
+// A con la columna i tomada de B. Se ordenan A, B, AB_1, ..., AB_k.
+fn saltelli(k: usize, puntos: u32) -> Vec<Vec<f64>> {
+    let base = sobol(2 * k, puntos);
+    let a: Vec<Vec<f64>> = base.iter().map(|p| p[..k].to_vec()).collect();
+    let b: Vec<Vec<f64>> = base.iter().map(|p| p[k..].to_vec()).collect();
+    let mut diseno = a.clone();
+    diseno.extend(b.iter().cloned());
+    for i in 0..k {
+        diseno.extend(a.iter().zip(&b).map(|(fa, fb)| {
+            let mut ab = fa.clone();
+            ab[i] = fb[i];
+            ab
+        }));
+    }
+    diseno
+}
+
+// Índices de primer orden (estimador de Saltelli 2010) y totales (Jansen).
+fn indices_sobol(salidas: &[Vec<f64>], n: usize, parametros: &[String], metricas: &[String]) -> Sensibilidad {
+    let mut resultado = Vec::new();
+    for (m, metrica) in metricas.iter().enumerate() {
+        let f = |bloque: usize, fila: usize| salidas[bloque * n + fila][m];
+        let todas: Vec<f64> = (0..2 * n).map(|i| salidas[i][m]).collect();
+        let media = todas.iter().sum::<f64>() / todas.len() as f64;
+        let varianza = todas.iter().map(|y| (y - media).powi(2)).sum::<f64>() / todas.len() as f64;
+        for (i, parametro) in parametros.iter().enumerate() {
+            let (mut primero, mut total) = (0.0, 0.0);
+            for fila in 0..n {
+                let (fa, fb, fab) = (f(0, fila), f(1, fila), f(2 + i, fila));
+                primero += fb * (fab - fa);
+                total += (fa - fab).powi(2);
+            }
+            let (s, st) = if varianza > 0.0 {
+                (primero / n as f64 / varianza, total / (2.0 * n as f64) / varianza)
+            } else {
+                (0.0, 0.0)
+            };
+            resultado.push(Efecto { metrica: metrica.clone(), parametro: parametro.clone(), valores: [s, st, 0.0] });
+        }
+    }
+    Sensibilidad::Sobol(resultado)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    fn nombres(prefijo: &str, k: usize) -> Vec<String> {
+        (0..k).map(|i| format!("{}{}", prefijo, i + 1)).collect()
+    }
+
+    #[test]
+    fn cada_coordenada_de_sobol_reparte_los_puntos_en_estratos() {
+        // Con el origen, los primeros 2^m puntos caen uno en cada intervalo de ancho 2^-m.
+        let puntos = sobol(DIMENSIONES_SOBOL, 63);
+        for j in 0..DIMENSIONES_SOBOL {
+            let mut estratos = [false; 64];
+            estratos[0] = true;
+            for p in &puntos {
+                estratos[(p[j] * 64.0) as usize] = true;
+            }
+            assert!(estratos.iter().all(|&e| e), "dimensión {}", j);
+        }
+    }
+
+    #[test]
+    fn indices_de_sobol_de_la_funcion_de_ishigami() {
+        // f = sen x1 + a·sen² x2 + b·x3⁴·sen x1 en [-π, π]³, con a = 7 y b = 0.1.
+        let ishigami = |u: &[f64]| {
+            let x: Vec<f64> = u.iter().map(|v| -PI + 2.0 * PI * v).collect();
+            x[0].sin() + 7.0 * x[1].sin().powi(2) + 0.1 * x[2].powi(4) * x[0].sin()
+        };
+        let n = 1 << 14;
+        let salidas: Vec<Vec<f64>> = saltelli(3, n).iter().map(|u| vec![ishigami(u)]).collect();
+        let sensibilidad = indices_sobol(&salidas, n as usize, &nombres("x", 3), &["f".to_string()]);
+        let primero = [0.3139, 0.4424, 0.0];
+        let total = [0.5576, 0.4424, 0.2437];
+        for (i, e) in sensibilidad.efectos().iter().enumerate() {
+            assert!((e.valores[0] - primero[i]).abs() < 0.02, "{:?}", e);
+            assert!((e.valores[1] - total[i]).abs() < 0.02, "{:?}", e);
+        }
+    }
+
+    #[test]
+    fn morris_da_las_pendientes_de_una_funcion_lineal() {
+        let mut rng = Generador::seed_from_u64(1);
+        let unitarios = morris(3, 20, 4, &mut rng);
+        let salidas: Vec<Vec<f64>> = unitarios.iter().map(|x| vec![2.0 * x[0] - x[1]]).collect();
+        let sensibilidad = efectos_morris(&unitarios, &salidas, &nombres("x", 3), &["f".to_string()]);
+        for (e, pendiente) in sensibilidad.efectos().iter().zip([2.0, -1.0, 0.0]) {
+            let [mu, mu_estrella, sigma] = e.valores;
+            assert!((mu - pendiente).abs() < 1e-12 && (mu_estrella - f64::abs(pendiente)).abs() < 1e-12, "{:?}", e);
+            assert!(sigma < 1e-12, "{:?}", e);
+        }
+        assert_eq!(sensibilidad.ranking("f")[0].0, "x1");
+    }
+
+    #[test]
+    fn las_trayectorias_de_morris_no_salen_del_cubo() {
+        let mut rng = Generador::seed_from_u64(3);
+        for niveles in 2..=7 {
+            let puntos = morris(4, 50, niveles, &mut rng);
+            assert!(puntos.iter().flatten().all(|x| (0.0..=1.0).contains(x)), "niveles = {}", niveles);
+        }
+    }
+}
@@ -0,0 +1,351 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Una fecha del calendario gregoriano (con años bisiestos).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Fecha {
+    pub anio: i32,
+    pub mes: u32,
+    pub dia: u32,
+}
+
+impl Fecha {
+    /// None si el día no existe en ese mes.
+    pub fn new(anio: i32, mes: u32, dia: u32) -> Option<Self> {
+        ((1..=12).contains(&mes) && (1..=dias_del_mes(anio, mes)).contains(&dia))
+            .then_some(Fecha { anio, mes, dia })
+    }
+
+    /// La fecha `n` días después (o antes, si es negativo).
+    pub fn mas_dias(self, n: i64) -> Self {
+        Fecha::desde_dias(self.dias() + n)
+    }
+
+    /// Mes y día, sin el año.
+    pub fn mes_dia(&self) -> MesDia {
+        MesDia { mes: self.mes, dia: self.dia }
+    }
+
+    // Días desde el 1970-01-01 (algoritmo de Howard Hinnant).
+    fn dias(&self) -> i64 {
+        let anio = self.anio as i64 - i64::from(self.mes <= 2);
+        let era = anio.div_euclid(400);
+        let anio_era = anio - era * 400;
+        let mes = self.mes as i64;
+        let dia_anio = (153 * (if mes > 2 { mes - 3 } else { mes + 9 }) + 2) / 5 + self.dia as i64 - 1;
+        let dia_era = anio_era * 365 + anio_era / 4 - anio_era / 100 + dia_anio;
+        era * 146_097 + dia_era - 719_468
+    }
+
+    fn desde_dias(dias: i64) -> Self {
+        let z = dias + 719_468;
+        let era = z.div_euclid(146_097);
+        let dia_era = z - era * 146_097;
+        let anio_era = (dia_era - dia_era / 1460 + dia_era / 36_524 - dia_era / 146_096) / 365;
+        let dia_anio = dia_era - (365 * anio_era + anio_era / 4 - anio_era / 100);
+        let m = (5 * dia_anio + 2) / 153;
+        let dia = (dia_anio - (153 * m + 2) / 5 + 1) as u32;
+        let mes = if m < 10 { m + 3 } else { m - 9 } as u32;
+        let anio = anio_era + era * 400 + i64::from(mes <= 2);
+        Fecha { anio: anio as i32, mes, dia }
+    }
+}
+
+fn es_bisiesto(anio: i32) -> bool {
+    (anio % 4 == 0 && anio % 100 != 0) || anio % 400 == 0
+}
+
+fn dias_del_mes(anio: i32, mes: u32) -> u32 {
+    match mes {
+        2 if es_bisiesto(anio) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl fmt::Display for Fecha {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.anio, self.mes, self.dia)
+    }
+}
+
+impl TryFrom<String> for Fecha {
+    type Error = String;
+
+    // "AAAA-MM-DD".
+    fn try_from(texto: String) -> Result<Self, String> {
+        let invalida = || format!("fecha inválida '{}': se espera AAAA-MM-DD", texto);
+        let mut partes = texto.splitn(3, '-');
+        let (Some(anio), Some(mes), Some(dia)) = (partes.next(), partes.next(), partes.next()) else {
+            return Err(invalida());
+        };
+        let (Ok(anio), Ok(mes), Ok(dia)) = (anio.parse(), mes.parse(), dia.parse()) else {
+            return Err(invalida());
+        };
+        Fecha::new(anio, mes, dia).ok_or_else(invalida)
+    }
+}
+
+impl From<Fecha> for String {
+    fn from(fecha: Fecha) -> Self {
+        fecha.to_string()
+    }
+}
+
+/// Un día del año, sin el año: "MM-DD".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct MesDia {
+    pub mes: u32,
+    pub dia: u32,
+}
+
+impl fmt::Display for MesDia {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}-{:02}", self.mes, self.dia)
+    }
+}
+
+impl TryFrom<String> for MesDia {
+    type Error = String;
+
+    fn try_from(texto: String) -> Result<Self, String> {
+        let invalida = || format!("día del año inválido '{}': se espera MM-DD", texto);
+        let (mes, dia) = texto.split_once('-').ok_or_else(invalida)?;
+        let (Ok(mes), Ok(dia)) = (mes.parse(), dia.parse()) else {
+            return Err(invalida());
+        };
+        // Un año bisiesto admite todos los días, incluido el 29 de febrero.
+        Fecha::new(2000, mes, dia).map(|f| f.mes_dia()).ok_or_else(invalida)
+    }
+}
+
+impl From<MesDia> for String {
+    fn from(mes_dia: MesDia) -> Self {
+        mes_dia.to_string()
+    }
+}
+
+/// Parte del año entre dos días, ambos incluidos. Si `desde` es posterior
+/// a `hasta`, cruza el fin de año (de noviembre a febrero, por ejemplo).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Temporada {
+    pub desde: MesDia,
+    pub hasta: MesDia,
+}
+
+impl Temporada {
+    pub fn contiene(&self, fecha: Fecha) -> bool {
+        let hoy = fecha.mes_dia();
+        if self.desde <= self.hasta {
+            self.desde <= hoy && hoy <= self.hasta
+        } else {
+            hoy >= self.desde || hoy <= self.hasta
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Hemisferio {
+    #[default]
+    Norte,
+    Sur,
+}
+
+/// Estaciones meteorológicas: trimestres completos (en el norte, el
+/// invierno va de diciembre a febrero).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Estacion {
+    Primavera,
+    Verano,
+    #[serde(rename = "otoño")]
+    Otono,
+    Invierno,
+}
+
+impl Estacion {
+    pub fn de(fecha: Fecha, hemisferio: Hemisferio) -> Self {
+        const NORTE: [Estacion; 4] = [Estacion::Invierno, Estacion::Primavera, Estacion::Verano, Estacion::Otono];
+        let trimestre = (fecha.mes % 12 / 3) as usize;
+        match hemisferio {
+            Hemisferio::Norte => NORTE[trimestre],
+            Hemisferio::Sur => NORTE[(trimestre + 2) % 4],
+        }
+    }
+}
+
+impl fmt::Display for Estacion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Estacion::Primavera => "primavera",
+            Estacion::Verano => "verano",
+            Estacion::Otono => "otoño",
+            Estacion::Invierno => "invierno",
+        })
+    }
+}
+
+/// Cuánto se multiplica cada tasa durante una estación (1: no cambia).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Forzamiento {
+    /// Tasa de rebrote del pasto.
+    pub rebrote: f64,
+    /// Mortalidad diaria de los herbívoros (enfermedad, frío).
+    pub mortalidad: f64,
+    /// Consumo diario de los lobos (mínimo y óptimo).
+    pub consumo_lobo: f64,
+}
+
+impl Default for Forzamiento {
+    fn default() -> Self {
+        Forzamiento { rebrote: 1.0, mortalidad: 1.0, consumo_lobo: 1.0 }
+    }
+}
+
+/// El forzamiento de cada estación; las que no se dan no cambian nada.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Estaciones {
+    pub primavera: Forzamiento,
+    pub verano: Forzamiento,
+    #[serde(alias = "otoño")]
+    pub otono: Forzamiento,
+    pub invierno: Forzamiento,
+}
+
+impl Estaciones {
+    pub fn de(&self, estacion: Estacion) -> Forzamiento {
+        match estacion {
+            Estacion::Primavera => self.primavera,
+            Estacion::Verano => self.verano,
+            Estacion::Otono => self.otono,
+            Estacion::Invierno => self.invierno,
+        }
+    }
+}
+
+/// Qué fecha es cada día de la simulación y qué estación le toca.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Calendario {
+    /// Fecha del día 1.
+    pub inicio: Fecha,
+    #[serde(default)]
+    pub hemisferio: Hemisferio,
+    #[serde(default)]
+    pub estaciones: Estaciones,
+}
+
+impl Calendario {
+    pub fn fecha(&self, dia: u32) -> Fecha {
+        self.inicio.mas_dias(dia as i64 - 1)
+    }
+
+    pub fn estacion(&self, dia: u32) -> Estacion {
+        Estacion::de(self.fecha(dia), self.hemisferio)
+    }
+
+    /// Cómo cambian las tasas ese día.
+    pub fn forzamiento(&self, dia: u32) -> Forzamiento {
+        self.estaciones.de(self.estacion(dia))
+    }
+
+    /// "2024-03-21, primavera".
+    pub fn describir(&self, dia: u32) -> String {
+        format!("{}, {}", self.fecha(dia), self.estacion(dia))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fecha(texto: &str) -> Fecha {
+        Fecha::try_from(texto.to_string()).unwrap()
+    }
+
+    #[test]
+    fn cuenta_los_dias_con_los_bisiestos() {
+        assert!(Fecha::new(2024, 2, 29).is_some());
+        assert!(Fecha::new(2023, 2, 29).is_none());
+        assert!(Fecha::new(1900, 2, 29).is_none());
+        assert!(Fecha::new(2000, 2, 29).is_some());
+        assert!(Fecha::new(2023, 4, 31).is_none() && Fecha::new(2023, 13, 1).is_none());
+        assert_eq!(fecha("1970-01-01").dias(), 0);
+
+        // Día por día durante dos siglos, sin saltos ni repeticiones.
+        let mut hoy = fecha("1899-12-31");
+        for n in 1..=73_000 {
+            let manana = hoy.mas_dias(1);
+            let esperado = if hoy.dia < dias_del_mes(hoy.anio, hoy.mes) {
+                Fecha { dia: hoy.dia + 1, ..hoy }
+            } else if hoy.mes < 12 {
+                Fecha { mes: hoy.mes + 1, dia: 1, ..hoy }
+            } else {
+                Fecha { anio: hoy.anio + 1, mes: 1, dia: 1 }
+            };
+            assert_eq!(manana, esperado);
+            assert_eq!(manana.mas_dias(-n), fecha("1899-12-31"));
+            hoy = manana;
+        }
+        assert_eq!(fecha("2024-02-28").mas_dias(366), fecha("2025-02-28"));
+    }
+
+    #[test]
+    fn lee_y_escribe_fechas_y_dias_del_anio() {
+        assert_eq!(fecha("2024-03-05").to_string(), "2024-03-05");
+        for mala in ["2024-02-30", "2024-3", "hoy", "2024-03-05x"] {
+            assert!(Fecha::try_from(mala.to_string()).is_err(), "{}", mala);
+        }
+        assert_eq!(MesDia::try_from("02-29".to_string()), Ok(MesDia { mes: 2, dia: 29 }));
+        assert!(MesDia::try_from("02-30".to_string()).is_err());
+        assert_eq!(serde_json::to_string(&fecha("2024-03-05")).unwrap(), "\"2024-03-05\"");
+    }
+
+    #[test]
+    fn una_temporada_puede_cruzar_el_fin_de_anio() {
+        let dia = |texto: &str| MesDia::try_from(texto.to_string()).unwrap();
+        let verano = Temporada { desde: dia("06-01"), hasta: dia("08-31") };
+        assert!(verano.contiene(fecha("2024-06-01")) && verano.contiene(fecha("2024-08-31")));
+        assert!(!verano.contiene(fecha("2024-09-01")) && !verano.contiene(fecha("2024-05-31")));
+
+        let invierno = Temporada { desde: dia("11-15"), hasta: dia("02-15") };
+        for dentro in ["2024-11-15", "2024-12-31", "2025-01-01", "2025-02-15"] {
+            assert!(invierno.contiene(fecha(dentro)), "{}", dentro);
+        }
+        for fuera in ["2024-11-14", "2025-02-16", "2025-07-01"] {
+            assert!(!invierno.contiene(fecha(fuera)), "{}", fuera);
+        }
+    }
+
+    #[test]
+    fn las_estaciones_cambian_el_primero_de_cada_trimestre() {
+        let norte = Forzamiento { rebrote: 0.2, ..Forzamiento::default() };
+        let calendario = Calendario {
+            inicio: fecha("2023-11-30"),
+            hemisferio: Hemisferio::Norte,
+            estaciones: Estaciones { invierno: norte, ..Estaciones::default() },
+        };
+        assert_eq!(calendario.fecha(1), fecha("2023-11-30"));
+        assert_eq!(calendario.estacion(1), Estacion::Otono);
+        assert_eq!(calendario.describir(2), "2023-12-01, invierno");
+        assert_eq!(calendario.forzamiento(1), Forzamiento::default());
+        assert_eq!(calendario.forzamiento(2), norte);
+        // 2024 es bisiesto: el 29 de febrero sigue en invierno.
+        assert_eq!(calendario.describir(92), "2024-02-29, invierno");
+        assert_eq!(calendario.describir(93), "2024-03-01, primavera");
+
+        // En el sur las estaciones están corridas medio año.
+        let sur = Calendario { hemisferio: Hemisferio::Sur, ..calendario };
+        let pares = [(1, Estacion::Primavera), (2, Estacion::Verano), (93, Estacion::Otono), (185, Estacion::Invierno)];
+        for (dia, estacion) in pares {
+            assert_eq!(sur.estacion(dia), estacion, "{}", sur.fecha(dia));
+        }
+    }
+}
@@ -0,0 +1,331 @@
+use crate::herbivoro::Herbivoro;
+use crate::lobo::Lobo;
+use crate::modelo::CurvaCrecimiento;
+use crate::organismo::Generador;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Lo que encuentra un depredador de una especie: la presa que elegiría
+/// dentro de ella y cuántas tiene disponibles.
+pub struct Oferta<'a> {
+    pub presa: &'a Herbivoro,
+    pub disponibles: usize,
+}
+
+/// Cómo elige un depredador a su presa. Primero la especie, entre las que
+/// tienen presas disponibles; después, dentro de ella, la de mayor puntaje.
+pub trait EstrategiaCaza {
+    /// Cuánto le conviene esta presa frente a las de su especie. Los empates
+    /// se sortean.
+    fn puntaje(&self, presa: &Herbivoro) -> f64;
+
+    /// Cuál de las `ofertas` sale a cazar (None si ninguna le conviene).
+    /// Por defecto, cualquiera de las especies con la misma probabilidad.
+    fn elegir_especie(&self, ofertas: &[Oferta], rng: &mut Generador) -> Option<usize> {
+        (!ofertas.is_empty()).then(|| rng.random_range(0..ofertas.len()))
+    }
+}
+
+/// La más pesada: la regla original del lobo.
+pub struct MasPesada;
+
+impl EstrategiaCaza for MasPesada {
+    fn puntaje(&self, presa: &Herbivoro) -> f64 {
+        presa.peso
+    }
+}
+
+/// Cualquiera, sin preferencias.
+pub struct Aleatoria;
+
+impl EstrategiaCaza for Aleatoria {
+    fn puntaje(&self, _presa: &Herbivoro) -> f64 {
+        0.0
+    }
+}
+
+/// La más joven de las que tienen la edad mínima de presa.
+pub struct MasJoven;
+
+impl EstrategiaCaza for MasJoven {
+    fn puntaje(&self, presa: &Herbivoro) -> f64 {
+        -(presa.edad as f64)
+    }
+}
+
+/// La más vieja.
+pub struct MasVieja;
+
+impl EstrategiaCaza for MasVieja {
+    fn puntaje(&self, presa: &Herbivoro) -> f64 {
+        presa.edad as f64
+    }
+}
+
+/// La de peor condición: la que menos pesa frente a lo esperado para su
+/// edad y la que menos comió hoy.
+pub struct MasDebil;
+
+impl EstrategiaCaza for MasDebil {
+    fn puntaje(&self, presa: &Herbivoro) -> f64 {
+        let esperado = presa.especie.crecimiento.peso(presa.edad as f64);
+        let condicion = if esperado > 0.0 { (presa.peso / esperado).min(1.0) } else { 1.0 };
+        -(condicion * presa.saciedad)
+    }
+}
+
+/// Dieta óptima (Charnov): cada presa rinde su peso en comida y lleva
+/// `manipulacion + manipulacion_por_kg · peso` días atraparla y comerla.
+/// Las especies entran a la dieta de la más rentable (kg por día de
+/// manipulación) a la menos, mientras rindan más que lo que se gana
+/// cazando solo las anteriores; entre las elegidas caza la que encuentra
+/// primero, proporcional a cuántas hay.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DietaOptima {
+    /// Días de manipulación por presa, sea del tamaño que sea.
+    pub manipulacion: f64,
+    /// Días de manipulación adicionales por kg de presa.
+    #[serde(default)]
+    pub manipulacion_por_kg: f64,
+    /// Presas que encuentra por día por cada presa disponible.
+    pub encuentro: f64,
+}
+
+impl DietaOptima {
+    fn manipulacion(&self, presa: &Herbivoro) -> f64 {
+        self.manipulacion + self.manipulacion_por_kg * presa.peso
+    }
+}
+
+impl EstrategiaCaza for DietaOptima {
+    fn puntaje(&self, presa: &Herbivoro) -> f64 {
+        presa.peso / self.manipulacion(presa)
+    }
+
+    fn elegir_especie(&self, ofertas: &[Oferta], rng: &mut Generador) -> Option<usize> {
+        let mut orden: Vec<usize> = (0..ofertas.len()).collect();
+        orden.sort_by(|&a, &b| self.puntaje(ofertas[b].presa).total_cmp(&self.puntaje(ofertas[a].presa)));
+
+        // Se agregan especies mientras su rentabilidad supere la tasa de
+        // ganancia (kg por día) de la dieta que ya tiene.
+        let (mut comida, mut tiempo) = (0.0, 1.0);
+        let mut dieta = Vec::new();
+        for i in orden {
+            let Oferta { presa, disponibles } = ofertas[i];
+            if !dieta.is_empty() && self.puntaje(presa) < comida / tiempo {
+                break;
+            }
+            let encuentros = self.encuentro * disponibles as f64;
+            comida += encuentros * presa.peso;
+            tiempo += encuentros * self.manipulacion(presa);
+            dieta.push((i, encuentros));
+        }
+
+        let total: f64 = dieta.iter().map(|(_, e)| e).sum();
+        if total <= 0.0 {
+            return dieta.first().map(|(i, _)| *i);
+        }
+        let mut sorteo = rng.random_range(0.0..total);
+        for &(i, encuentros) in &dieta {
+            if sorteo < encuentros {
+                return Some(i);
+            }
+            sorteo -= encuentros;
+        }
+        dieta.last().map(|(i, _)| *i)
+    }
+}
+
+// Pesos por debajo de este (un cachorro recién nacido) cuentan como este,
+// para que la relación de pesos sea siempre finita.
+const PESO_MINIMO: f64 = 1e-6;
+
+/// Probabilidad de que un ataque termine en caza, como un modelo logístico:
+///
+/// logit(p) = logit(base) − efecto_peso · log2(peso presa / peso lobo)
+///          + vulnerabilidad_edad (si la presa es cría o vieja)
+///          + efecto_hambre · hambre
+///
+/// donde el hambre va de 0 (reserva en el mínimo para salir a cazar) a 1
+/// (reserva vacía). Si falla, la presa escapa y el lobo pierde `costo_fallo`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExitoCaza {
+    /// Probabilidad contra una presa adulta de su mismo peso, sin hambre.
+    pub base: f64,
+    /// Cuánto baja el logit cada vez que la presa duplica el peso del lobo.
+    pub efecto_peso: f64,
+    /// Cuánto sube el logit contra crías (antes de su madurez) y viejas
+    /// (en el último cuarto de su edad máxima).
+    #[serde(default)]
+    pub vulnerabilidad_edad: f64,
+    /// Cuánto sube el logit con la reserva vacía: el hambre lo hace insistir.
+    #[serde(default)]
+    pub efecto_hambre: f64,
+    /// Reserva (kg) que gasta en un ataque fallido.
+    pub costo_fallo: f64,
+}
+
+impl ExitoCaza {
+    pub fn probabilidad(&self, lobo: &Lobo, presa: &Herbivoro) -> f64 {
+        let relacion = presa.peso.max(PESO_MINIMO) / lobo.peso.max(PESO_MINIMO);
+        let especie = &presa.especie;
+        let vulnerable = presa.edad < especie.edad_madurez
+            || presa.edad as f64 >= 0.75 * especie.edad_maxima as f64;
+        let hambre = if lobo.reserva_minima > 0.0 {
+            ((lobo.reserva_minima - lobo.reserva) / lobo.reserva_minima).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let logit = (self.base / (1.0 - self.base)).ln()
+            - self.efecto_peso * relacion.log2()
+            + if vulnerable { self.vulnerabilidad_edad } else { 0.0 }
+            + self.efecto_hambre * hambre;
+        1.0 / (1.0 + (-logit).exp())
+    }
+}
+
+/// Estrategia elegida por un depredador. En el escenario se escribe como
+/// `estrategia = "mas_joven"` o
+/// `estrategia = { dieta_optima = { manipulacion = 0.5, encuentro = 0.01 } }`.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Estrategia {
+    #[default]
+    MasPesada,
+    Aleatoria,
+    MasJoven,
+    MasVieja,
+    MasDebil,
+    DietaOptima(DietaOptima),
+}
+
+impl Estrategia {
+    fn estrategia(&self) -> &dyn EstrategiaCaza {
+        match self {
+            Estrategia::MasPesada => &MasPesada,
+            Estrategia::Aleatoria => &Aleatoria,
+            Estrategia::MasJoven => &MasJoven,
+            Estrategia::MasVieja => &MasVieja,
+            Estrategia::MasDebil => &MasDebil,
+            Estrategia::DietaOptima(d) => d,
+        }
+    }
+}
+
+impl EstrategiaCaza for Estrategia {
+    fn puntaje(&self, presa: &Herbivoro) -> f64 {
+        self.estrategia().puntaje(presa)
+    }
+
+    fn elegir_especie(&self, ofertas: &[Oferta], rng: &mut Generador) -> Option<usize> {
+        self.estrategia().elegir_especie(ofertas, rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::herbivoro::ParametrosHerbivoro;
+    use crate::organismo::Sexo;
+    use rand::SeedableRng;
+    use std::sync::Arc;
+
+    // Una cabra de `edad` días con el peso esperado para ella (por `condicion`)
+    // que hoy comió `saciedad` de lo que quería.
+    fn cabra(edad: u32, condicion: f64, saciedad: f64) -> Herbivoro {
+        let mut cabra = Herbivoro::new(Arc::new(ParametrosHerbivoro::cabra()), Sexo::Hembra);
+        cabra.edad = edad;
+        cabra.peso = condicion * cabra.especie.crecimiento.peso(edad as f64);
+        cabra.saciedad = saciedad;
+        cabra
+    }
+
+    // La presa de mayor puntaje.
+    fn preferida(estrategia: Estrategia, presas: &[Herbivoro]) -> usize {
+        (0..presas.len())
+            .max_by(|&a, &b| estrategia.puntaje(&presas[a]).total_cmp(&estrategia.puntaje(&presas[b])))
+            .unwrap()
+    }
+
+    #[test]
+    fn cada_estrategia_prefiere_su_presa() {
+        let presas = [cabra(60, 1.0, 1.0), cabra(4000, 1.0, 1.0), cabra(600, 0.6, 0.3)];
+        assert_eq!(preferida(Estrategia::MasPesada, &presas), 1);
+        assert_eq!(preferida(Estrategia::MasJoven, &presas), 0);
+        assert_eq!(preferida(Estrategia::MasVieja, &presas), 1);
+        assert_eq!(preferida(Estrategia::MasDebil, &presas), 2);
+        assert!(presas.iter().all(|p| Estrategia::Aleatoria.puntaje(p) == 0.0));
+    }
+
+    #[test]
+    fn la_dieta_optima_deja_las_presas_poco_rentables_si_abundan_las_buenas() {
+        let conejo = Herbivoro { peso: 2.0, ..Herbivoro::new(Arc::new(ParametrosHerbivoro::conejo()), Sexo::Macho) };
+        let cabra = cabra(1000, 1.0, 1.0);
+        let dieta = DietaOptima { manipulacion: 1.0, manipulacion_por_kg: 0.0, encuentro: 0.01 };
+        let mut rng = Generador::seed_from_u64(3);
+        let elegidas = |cabras: usize, rng: &mut Generador| {
+            let ofertas = [Oferta { presa: &conejo, disponibles: 100 }, Oferta { presa: &cabra, disponibles: cabras }];
+            let mut veces = [0; 2];
+            for _ in 0..1000 {
+                veces[dieta.elegir_especie(&ofertas, rng).unwrap()] += 1;
+            }
+            veces
+        };
+
+        // Con muchas cabras, cazar conejos rinde menos que seguir buscando cabras.
+        assert_eq!(elegidas(100, &mut rng), [0, 1000]);
+        // Con pocas, los conejos entran a la dieta y son los que más encuentra.
+        let veces = elegidas(2, &mut rng);
+        assert!(veces[0] > veces[1] && veces[1] > 0, "{:?}", veces);
+        assert_eq!(dieta.elegir_especie(&[], &mut rng), None);
+    }
+
+    #[test]
+    fn la_estrategia_se_elige_en_el_escenario() {
+        #[derive(Deserialize)]
+        struct Lobo {
+            estrategia: Estrategia,
+        }
+        let leer = |texto: &str| toml::from_str::<Lobo>(texto).map(|l| l.estrategia).ok();
+        assert_eq!(leer("estrategia = \"mas_joven\""), Some(Estrategia::MasJoven));
+        assert_eq!(
+            leer("estrategia = { dieta_optima = { manipulacion = 0.5, encuentro = 0.01 } }"),
+            Some(Estrategia::DietaOptima(DietaOptima { manipulacion: 0.5, manipulacion_por_kg: 0.0, encuentro: 0.01 }))
+        );
+        assert_eq!(leer("estrategia = \"la_mas_lenta\""), None);
+    }
+
+    fn logit(p: f64) -> f64 {
+        (p / (1.0 - p)).ln()
+    }
+
+    #[test]
+    fn el_exito_de_la_caza_sigue_el_modelo_logistico() {
+        let exito = ExitoCaza { base: 0.3, efecto_peso: 1.5, vulnerabilidad_edad: 0.8, efecto_hambre: 2.0, costo_fallo: 1.0 };
+        let adulta = cabra(1000, 1.0, 1.0);
+        let mut lobo = Lobo::new_con_sexo(Sexo::Macho);
+        lobo.peso = adulta.peso;
+        lobo.reserva = lobo.reserva_minima;
+        let cerca = |a: f64, b: f64| (a - b).abs() < 1e-9;
+
+        // Contra una presa adulta de su peso y sin hambre, la probabilidad base.
+        assert!(cerca(exito.probabilidad(&lobo, &adulta), 0.3));
+        // Cada duplicación del peso de la presa le resta `efecto_peso` al logit.
+        let pesada = Herbivoro { peso: 4.0 * adulta.peso, ..cabra(1000, 1.0, 1.0) };
+        assert!(cerca(logit(exito.probabilidad(&lobo, &pesada)), logit(0.3) - 3.0));
+        // Crías y viejas son más fáciles.
+        for vulnerable in [Herbivoro { peso: adulta.peso, ..cabra(100, 1.0, 1.0) }, Herbivoro { peso: adulta.peso, ..cabra(4000, 1.0, 1.0) }] {
+            assert!(cerca(logit(exito.probabilidad(&lobo, &vulnerable)), logit(0.3) + 0.8));
+        }
+        // Con la reserva vacía insiste más; con la mitad, la mitad.
+        lobo.reserva = 0.0;
+        assert!(cerca(logit(exito.probabilidad(&lobo, &adulta)), logit(0.3) + 2.0));
+        lobo.reserva = lobo.reserva_minima / 2.0;
+        assert!(cerca(logit(exito.probabilidad(&lobo, &adulta)), logit(0.3) + 1.0));
+        // Un cachorro sin peso todavía da una probabilidad válida.
+        lobo.peso = 0.0;
+        assert!((0.0..=1.0).contains(&exito.probabilidad(&lobo, &adulta)));
+    }
+}
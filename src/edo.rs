@@ -0,0 +1,711 @@
+//! Contraparte determinista del modelo de agentes: modelos depredador-presa
+//! de Lotka-Volterra, integrador RK4 y análisis de estabilidad de los puntos fijos.
+//!
+//! Las poblaciones se miden en individuos y el tiempo en días, igual que en
+//! `Simulador`, así que una trayectoria se puede superponer a una corrida.
+
+use crate::escenario::Escenario;
+use crate::espacio::Espacio;
+use crate::herbivoro::ParametrosHerbivoro;
+use crate::lobo::ParametrosLobo;
+use crate::modelo::CurvaCrecimiento;
+use crate::vegetacion::ParametrosVegetacion;
+use std::fmt::Write as _;
+use std::ops::{Add, Div, Mul, Sub};
+
+/// Sistema autónomo dx/dt = f(x) con `N` variables de estado.
+pub trait SistemaEdo<const N: usize> {
+    fn derivada(&self, x: &[f64; N]) -> [f64; N];
+    /// Puntos fijos con todas las poblaciones no negativas.
+    fn puntos_fijos(&self) -> Vec<[f64; N]>;
+}
+
+// Integración
+
+/// Un paso de Runge-Kutta clásico de orden 4.
+pub fn rk4_paso<const N: usize>(sistema: &impl SistemaEdo<N>, x: &[f64; N], h: f64) -> [f64; N] {
+    let mas = |a: &[f64; N], k: &[f64; N], f: f64| -> [f64; N] { std::array::from_fn(|i| a[i] + f * k[i]) };
+    let k1 = sistema.derivada(x);
+    let k2 = sistema.derivada(&mas(x, &k1, h / 2.0));
+    let k3 = sistema.derivada(&mas(x, &k2, h / 2.0));
+    let k4 = sistema.derivada(&mas(x, &k3, h));
+    std::array::from_fn(|i| x[i] + h / 6.0 * (k1[i] + 2.0 * k2[i] + 2.0 * k3[i] + k4[i]))
+}
+
+/// Integra desde `x0` hasta `dias` con paso `h` y devuelve un punto por día
+/// (incluido el día 0). Las poblaciones no bajan de 0.
+pub fn integrar<const N: usize>(sistema: &impl SistemaEdo<N>, x0: [f64; N], dias: u32, h: f64) -> Vec<(u32, [f64; N])> {
+    let pasos_por_dia = (1.0 / h).round().max(1.0) as u32;
+    let h = 1.0 / pasos_por_dia as f64;
+    let mut x = x0;
+    let mut trayectoria = Vec::with_capacity(dias as usize + 1);
+    trayectoria.push((0, x));
+    for dia in 1..=dias {
+        for _ in 0..pasos_por_dia {
+            x = rk4_paso(sistema, &x, h);
+            x.iter_mut().for_each(|v| *v = v.max(0.0));
+        }
+        trayectoria.push((dia, x));
+    }
+    trayectoria
+}
+
+/// Trayectoria en CSV: `dia` y una columna por variable.
+pub fn trayectoria_csv<const N: usize>(trayectoria: &[(u32, [f64; N])], columnas: [&str; N]) -> String {
+    let mut csv = format!("dia,{}\n", columnas.join(","));
+    for (dia, x) in trayectoria {
+        let valores: Vec<String> = x.iter().map(|v| format!("{:.4}", v)).collect();
+        let _ = writeln!(csv, "{},{}", dia, valores.join(","));
+    }
+    csv
+}
+
+// Estabilidad
+
+/// Número complejo mínimo para los autovalores.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Complejo {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Complejo {
+    pub fn new(re: f64, im: f64) -> Self {
+        Complejo { re, im }
+    }
+
+    pub fn modulo(&self) -> f64 {
+        self.re.hypot(self.im)
+    }
+}
+
+impl Add for Complejo {
+    type Output = Complejo;
+    fn add(self, o: Complejo) -> Complejo {
+        Complejo::new(self.re + o.re, self.im + o.im)
+    }
+}
+
+impl Sub for Complejo {
+    type Output = Complejo;
+    fn sub(self, o: Complejo) -> Complejo {
+        Complejo::new(self.re - o.re, self.im - o.im)
+    }
+}
+
+impl Mul for Complejo {
+    type Output = Complejo;
+    fn mul(self, o: Complejo) -> Complejo {
+        Complejo::new(self.re * o.re - self.im * o.im, self.re * o.im + self.im * o.re)
+    }
+}
+
+impl Div for Complejo {
+    type Output = Complejo;
+    fn div(self, o: Complejo) -> Complejo {
+        let d = o.re * o.re + o.im * o.im;
+        Complejo::new((self.re * o.re + self.im * o.im) / d, (self.im * o.re - self.re * o.im) / d)
+    }
+}
+
+/// Jacobiano numérico (diferencias centrales) de `sistema` en `x`.
+pub fn jacobiano<const N: usize>(sistema: &impl SistemaEdo<N>, x: &[f64; N]) -> [[f64; N]; N] {
+    let mut j = [[0.0; N]; N];
+    for k in 0..N {
+        let h = 1e-6 * x[k].abs().max(1.0);
+        let mut mas = *x;
+        let mut menos = *x;
+        mas[k] += h;
+        menos[k] -= h;
+        let (fm, fn_) = (sistema.derivada(&mas), sistema.derivada(&menos));
+        for i in 0..N {
+            j[i][k] = (fm[i] - fn_[i]) / (2.0 * h);
+        }
+    }
+    j
+}
+
+/// Autovalores de una matriz: polinomio característico (Faddeev-LeVerrier)
+/// y sus raíces por Durand-Kerner. Pensado para las matrices chicas de estos modelos.
+pub fn autovalores<const N: usize>(a: &[[f64; N]; N]) -> Vec<Complejo> {
+    // Coeficientes c[0..=N] de det(λI - A) = Σ c[k] λ^k, con c[N] = 1.
+    let mut c = vec![0.0; N + 1];
+    c[N] = 1.0;
+    let mut m = [[0.0; N]; N];
+    for k in 1..=N {
+        // M_k = A·M_{k-1} + c_{N-k+1}·I
+        let mut nueva = [[0.0; N]; N];
+        for i in 0..N {
+            for j in 0..N {
+                nueva[i][j] = (0..N).map(|l| a[i][l] * m[l][j]).sum::<f64>();
+            }
+            nueva[i][i] += c[N - k + 1];
+        }
+        m = nueva;
+        let traza: f64 = (0..N).map(|i| (0..N).map(|l| a[i][l] * m[l][i]).sum::<f64>()).sum();
+        c[N - k] = -traza / k as f64;
+    }
+
+    let evaluar = |z: Complejo| c.iter().rev().fold(Complejo::new(0.0, 0.0), |acc, &ck| acc * z + Complejo::new(ck, 0.0));
+    let semilla = Complejo::new(0.4, 0.9);
+    let mut raices: Vec<Complejo> = Vec::with_capacity(N);
+    let mut z = Complejo::new(1.0, 0.0);
+    for _ in 0..N {
+        raices.push(z);
+        z = z * semilla;
+    }
+    for _ in 0..500 {
+        let mut cambio: f64 = 0.0;
+        for i in 0..N {
+            let denominador = (0..N)
+                .filter(|&j| j != i)
+                .fold(Complejo::new(1.0, 0.0), |acc, j| acc * (raices[i] - raices[j]));
+            let paso = evaluar(raices[i]) / denominador;
+            raices[i] = raices[i] - paso;
+            cambio = cambio.max(paso.modulo());
+        }
+        if cambio < 1e-14 {
+            break;
+        }
+    }
+    // Limpia los restos numéricos de las raíces reales.
+    for r in &mut raices {
+        if r.im.abs() < 1e-9 * r.re.abs().max(1.0) {
+            r.im = 0.0;
+        }
+    }
+    raices
+}
+
+/// Estabilidad local según los autovalores del jacobiano.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Estabilidad {
+    /// Todas las partes reales negativas: las perturbaciones se apagan.
+    Estable,
+    /// Todas positivas (o nulas con alguna positiva): las perturbaciones crecen.
+    Inestable,
+    /// Partes reales de ambos signos.
+    Silla,
+    /// Alguna parte real nula y ninguna positiva: la linealización no decide
+    /// (p. ej. el centro de Lotka-Volterra clásico, con órbitas cerradas).
+    Neutral,
+}
+
+impl Estabilidad {
+    pub fn de(autovalores: &[Complejo]) -> Self {
+        // Lo que se considera parte real nula, relativo a la escala de los autovalores.
+        let escala = autovalores.iter().map(Complejo::modulo).fold(1e-12, f64::max);
+        let cero = 1e-7 * escala;
+        let positivos = autovalores.iter().any(|l| l.re > cero);
+        let negativos = autovalores.iter().any(|l| l.re < -cero);
+        let nulos = autovalores.iter().any(|l| l.re.abs() <= cero);
+        match (positivos, negativos, nulos) {
+            (true, true, _) => Estabilidad::Silla,
+            (true, false, _) => Estabilidad::Inestable,
+            (false, _, true) => Estabilidad::Neutral,
+            (false, _, false) => Estabilidad::Estable,
+        }
+    }
+}
+
+/// Un punto fijo con su análisis lineal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PuntoFijo<const N: usize> {
+    pub estado: [f64; N],
+    pub autovalores: Vec<Complejo>,
+    pub estabilidad: Estabilidad,
+}
+
+/// Puntos fijos del sistema con sus autovalores y su estabilidad.
+pub fn analizar<const N: usize>(sistema: &impl SistemaEdo<N>) -> Vec<PuntoFijo<N>> {
+    sistema.puntos_fijos().into_iter().map(|estado| {
+        let autovalores = autovalores(&jacobiano(sistema, &estado));
+        let estabilidad = Estabilidad::de(&autovalores);
+        PuntoFijo { estado, autovalores, estabilidad }
+    }).collect()
+}
+
+/// Punto fijo cercano a `x0` por el método de Newton (None si no converge).
+pub fn newton<const N: usize>(sistema: &impl SistemaEdo<N>, x0: [f64; N]) -> Option<[f64; N]> {
+    let mut x = x0;
+    for _ in 0..100 {
+        let f = sistema.derivada(&x);
+        let paso = resolver(jacobiano(sistema, &x), f)?;
+        let mut cambio: f64 = 0.0;
+        for i in 0..N {
+            x[i] -= paso[i];
+            cambio = cambio.max(paso[i].abs() / x[i].abs().max(1.0));
+        }
+        if !x.iter().all(|v| v.is_finite()) {
+            return None;
+        }
+        if cambio < 1e-12 {
+            return Some(x);
+        }
+    }
+    None
+}
+
+// Resuelve A·x = b por eliminación gaussiana con pivoteo parcial.
+pub(crate) fn resolver<const N: usize>(mut a: [[f64; N]; N], mut b: [f64; N]) -> Option<[f64; N]> {
+    for col in 0..N {
+        let pivote = (col..N).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivote][col].abs() < 1e-300 {
+            return None;
+        }
+        a.swap(col, pivote);
+        b.swap(col, pivote);
+        for fila in col + 1..N {
+            let factor = a[fila][col] / a[col][col];
+            let pivote = a[col];
+            for (v, p) in a[fila][col..].iter_mut().zip(&pivote[col..]) {
+                *v -= factor * p;
+            }
+            b[fila] -= factor * b[col];
+        }
+    }
+    let mut x = [0.0; N];
+    for fila in (0..N).rev() {
+        let resto: f64 = (fila + 1..N).map(|k| a[fila][k] * x[k]).sum();
+        x[fila] = (b[fila] - resto) / a[fila][fila];
+    }
+    Some(x)
+}
+
+// Parámetros a partir de las especies del simulador
+
+/// Tasas medias de una especie presa, estimadas de sus parámetros de agente.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TasasPresa {
+    /// r: nacimientos menos muertes per cápita por día, sin límite de comida.
+    pub crecimiento: f64,
+    /// K: individuos adultos que el pasto mantiene indefinidamente.
+    pub capacidad: f64,
+    /// Peso adulto (kg): lo que obtiene un lobo al cazarla.
+    pub peso: f64,
+}
+
+impl TasasPresa {
+    pub fn desde_herbivoro(especie: &ParametrosHerbivoro, vegetacion: &ParametrosVegetacion) -> Self {
+        let edad_maxima = especie.edad_maxima as f64;
+        // Mitad hembras, maduras una fracción de su vida, camada media.
+        let fraccion_madura = (edad_maxima - especie.edad_madurez as f64).max(0.0) / edad_maxima;
+        let camada = (especie.camada_minima + especie.camada_maxima) as f64 / 2.0;
+        let nacimientos = 0.5 * fraccion_madura * especie.probabilidad_parto * camada;
+        let muertes = especie.mortalidad_diaria + 1.0 / edad_maxima;
+
+        // El pasto rinde como mucho r·K/4 por día (máximo rendimiento sostenible).
+        let peso = especie.crecimiento.peso_maximo();
+        let rendimiento = vegetacion.tasa_rebrote * vegetacion.capacidad / 4.0;
+        let consumo = especie.ingesta_diaria * peso;
+        let capacidad = if consumo > 0.0 { rendimiento / consumo } else { f64::INFINITY };
+
+        TasasPresa { crecimiento: nacimientos - muertes, capacidad, peso }
+    }
+}
+
+/// Tasas medias del depredador, estimadas de los parámetros del lobo.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TasasDepredador {
+    /// m: muertes per cápita por día sin presas (agota la reserva llena).
+    pub mortalidad: f64,
+    /// Cachorros per cápita por día de una población bien alimentada.
+    pub natalidad: f64,
+    /// Consumo medio diario (kg).
+    pub consumo: f64,
+}
+
+impl TasasDepredador {
+    pub fn desde_lobo(lobo: &ParametrosLobo) -> Self {
+        let consumo = (lobo.nivel_minimo + lobo.nivel_optimo) / 2.0;
+        let camada = (1 + lobo.camada_maxima) as f64 / 2.0;
+        TasasDepredador {
+            mortalidad: consumo / lobo.reserva_maxima,
+            natalidad: 0.5 * lobo.probabilidad_cria * camada,
+            consumo,
+        }
+    }
+
+    /// Cachorros por presa cazada: comer `consumo` kg al día mantiene la natalidad máxima.
+    pub fn conversion(&self, presa: &TasasPresa) -> f64 {
+        self.natalidad * presa.peso / self.consumo
+    }
+
+    /// Días que tarda en consumir una presa antes de volver a cazar.
+    pub fn manipulacion(&self, presa: &TasasPresa) -> f64 {
+        presa.peso / self.consumo
+    }
+}
+
+/// Tasa de ataque `a` (presas por presa disponible, por lobo y por día).
+/// Con espacio es la fracción del mundo que un lobo recorre en un día;
+/// sin espacio cualquier lobo alcanza cualquier presa, así que vale 1.
+pub fn tasa_ataque(espacio: Option<&Espacio>, velocidad_lobo: u32) -> f64 {
+    match espacio {
+        None => 1.0,
+        Some(e) => {
+            let lado = (2 * e.radio_caza + 1) as f64;
+            let barrido = lado * (lado + 2.0 * velocidad_lobo as f64);
+            (barrido / e.celdas() as f64).min(1.0)
+        }
+    }
+}
+
+// Modelos. Estado: [presa, depredador] o [presa 1, presa 2, depredador].
+
+/// Lotka-Volterra clásico: dN = rN - aNP, dP = e·aNP - mP.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LotkaVolterra {
+    pub crecimiento: f64,
+    pub ataque: f64,
+    pub conversion: f64,
+    pub mortalidad: f64,
+}
+
+impl SistemaEdo<2> for LotkaVolterra {
+    fn derivada(&self, x: &[f64; 2]) -> [f64; 2] {
+        let [n, p] = *x;
+        let caza = self.ataque * n * p;
+        [self.crecimiento * n - caza, self.conversion * caza - self.mortalidad * p]
+    }
+
+    fn puntos_fijos(&self) -> Vec<[f64; 2]> {
+        let mut puntos = vec![[0.0, 0.0]];
+        if self.crecimiento > 0.0 && self.mortalidad > 0.0 && self.conversion > 0.0 && self.ataque > 0.0 {
+            puntos.push([self.mortalidad / (self.conversion * self.ataque), self.crecimiento / self.ataque]);
+        }
+        puntos
+    }
+}
+
+/// Presa con crecimiento logístico: dN = rN(1 - N/K) - aNP, dP = e·aNP - mP.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PresaLogistica {
+    pub crecimiento: f64,
+    pub capacidad: f64,
+    pub ataque: f64,
+    pub conversion: f64,
+    pub mortalidad: f64,
+}
+
+impl SistemaEdo<2> for PresaLogistica {
+    fn derivada(&self, x: &[f64; 2]) -> [f64; 2] {
+        let [n, p] = *x;
+        let caza = self.ataque * n * p;
+        [
+            self.crecimiento * n * (1.0 - n / self.capacidad) - caza,
+            self.conversion * caza - self.mortalidad * p,
+        ]
+    }
+
+    fn puntos_fijos(&self) -> Vec<[f64; 2]> {
+        let mut puntos = vec![[0.0, 0.0], [self.capacidad, 0.0]];
+        // Con r <= 0 la presa no sostiene lobos: el depredador quedaría negativo.
+        if self.crecimiento > 0.0 && self.mortalidad > 0.0 && self.conversion > 0.0 && self.ataque > 0.0 {
+            let n = self.mortalidad / (self.conversion * self.ataque);
+            if n < self.capacidad {
+                puntos.push([n, self.crecimiento / self.ataque * (1.0 - n / self.capacidad)]);
+            }
+        }
+        puntos
+    }
+}
+
+/// Rosenzweig-MacArthur: presa logística y respuesta funcional Holling tipo II
+/// con tiempo de manipulación `h`: la caza por lobo se satura en 1/h.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HollingII {
+    pub crecimiento: f64,
+    pub capacidad: f64,
+    pub ataque: f64,
+    pub manipulacion: f64,
+    pub conversion: f64,
+    pub mortalidad: f64,
+}
+
+impl HollingII {
+    // Presas cazadas por lobo y por día.
+    fn respuesta(&self, n: f64) -> f64 {
+        self.ataque * n / (1.0 + self.ataque * self.manipulacion * n)
+    }
+}
+
+impl SistemaEdo<2> for HollingII {
+    fn derivada(&self, x: &[f64; 2]) -> [f64; 2] {
+        let [n, p] = *x;
+        let caza = self.respuesta(n) * p;
+        [
+            self.crecimiento * n * (1.0 - n / self.capacidad) - caza,
+            self.conversion * caza - self.mortalidad * p,
+        ]
+    }
+
+    fn puntos_fijos(&self) -> Vec<[f64; 2]> {
+        let mut puntos = vec![[0.0, 0.0], [self.capacidad, 0.0]];
+        let neto = self.conversion - self.mortalidad * self.manipulacion;
+        if self.crecimiento > 0.0 && self.mortalidad > 0.0 && neto > 0.0 && self.ataque > 0.0 {
+            let n = self.mortalidad / (self.ataque * neto);
+            if n < self.capacidad {
+                let p = self.crecimiento / self.ataque
+                    * (1.0 - n / self.capacidad)
+                    * (1.0 + self.ataque * self.manipulacion * n);
+                puntos.push([n, p]);
+            }
+        }
+        puntos
+    }
+}
+
+/// Dos presas logísticas (sin competencia directa) y un depredador con
+/// respuesta Holling tipo II multiespecie: el tiempo que dedica a manipular
+/// una presa no lo puede usar para buscar la otra. Con `manipulacion` en 0
+/// la respuesta es lineal (Lotka-Volterra).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DosPresas {
+    pub crecimiento: [f64; 2],
+    pub capacidad: [f64; 2],
+    pub ataque: [f64; 2],
+    pub manipulacion: [f64; 2],
+    pub conversion: [f64; 2],
+    pub mortalidad: f64,
+}
+
+impl DosPresas {
+    // Presas de cada especie cazadas por lobo y por día.
+    fn respuesta(&self, n: [f64; 2]) -> [f64; 2] {
+        let saturacion = 1.0 + (0..2).map(|i| self.ataque[i] * self.manipulacion[i] * n[i]).sum::<f64>();
+        [0, 1].map(|i| self.ataque[i] * n[i] / saturacion)
+    }
+
+    // El subsistema con una sola de las presas.
+    fn solo(&self, i: usize) -> HollingII {
+        HollingII {
+            crecimiento: self.crecimiento[i],
+            capacidad: self.capacidad[i],
+            ataque: self.ataque[i],
+            manipulacion: self.manipulacion[i],
+            conversion: self.conversion[i],
+            mortalidad: self.mortalidad,
+        }
+    }
+}
+
+impl SistemaEdo<3> for DosPresas {
+    fn derivada(&self, x: &[f64; 3]) -> [f64; 3] {
+        let [n1, n2, p] = *x;
+        let [f1, f2] = self.respuesta([n1, n2]);
+        [
+            self.crecimiento[0] * n1 * (1.0 - n1 / self.capacidad[0]) - f1 * p,
+            self.crecimiento[1] * n2 * (1.0 - n2 / self.capacidad[1]) - f2 * p,
+            (self.conversion[0] * f1 + self.conversion[1] * f2) * p - self.mortalidad * p,
+        ]
+    }
+
+    fn puntos_fijos(&self) -> Vec<[f64; 3]> {
+        let [k1, k2] = self.capacidad;
+        let mut puntos = vec![[0.0, 0.0, 0.0], [k1, 0.0, 0.0], [0.0, k2, 0.0], [k1, k2, 0.0]];
+
+        // Depredador con una sola de las presas.
+        let mut solos = Vec::new();
+        for i in 0..2 {
+            if let Some(&[n, p]) = self.solo(i).puntos_fijos().get(2) {
+                let mut punto = [0.0, 0.0, p];
+                punto[i] = n;
+                puntos.push(punto);
+                solos.push(punto);
+            }
+        }
+
+        // Coexistencia: no tiene forma cerrada con respuesta tipo II, así que
+        // se busca con Newton desde una grilla de puntos de partida.
+        let escala = solos.iter().map(|p| p[2]).fold(1.0, f64::max);
+        let fracciones = [0.1, 0.3, 0.5, 0.7, 0.9];
+        let mut partidas = Vec::new();
+        for f1 in fracciones {
+            for f2 in fracciones {
+                for fp in [0.25, 0.5, 1.0, 2.0, 4.0] {
+                    partidas.push([f1 * k1, f2 * k2, fp * escala]);
+                }
+            }
+        }
+        for partida in partidas {
+            if let Some(x) = newton(self, partida)
+                && x.iter().all(|&v| v > 1e-9)
+                && !puntos.iter().any(|q| (0..3).all(|j| (q[j] - x[j]).abs() < 1e-6 * x[j].max(1.0)))
+            {
+                puntos.push(x);
+            }
+        }
+        puntos
+    }
+}
+
+impl LotkaVolterra {
+    pub fn desde_especies(presa: &TasasPresa, lobo: &TasasDepredador, ataque: f64) -> Self {
+        LotkaVolterra {
+            crecimiento: presa.crecimiento,
+            ataque,
+            conversion: lobo.conversion(presa),
+            mortalidad: lobo.mortalidad,
+        }
+    }
+}
+
+impl PresaLogistica {
+    pub fn desde_especies(presa: &TasasPresa, lobo: &TasasDepredador, ataque: f64) -> Self {
+        PresaLogistica {
+            crecimiento: presa.crecimiento,
+            capacidad: presa.capacidad,
+            ataque,
+            conversion: lobo.conversion(presa),
+            mortalidad: lobo.mortalidad,
+        }
+    }
+}
+
+impl HollingII {
+    pub fn desde_especies(presa: &TasasPresa, lobo: &TasasDepredador, ataque: f64) -> Self {
+        HollingII {
+            crecimiento: presa.crecimiento,
+            capacidad: presa.capacidad,
+            ataque,
+            manipulacion: lobo.manipulacion(presa),
+            conversion: lobo.conversion(presa),
+            mortalidad: lobo.mortalidad,
+        }
+    }
+
+    /// Modelo de media de campo para una presa del escenario (por nombre).
+    pub fn desde_escenario(escenario: &Escenario, presa: &str) -> Option<Self> {
+        let especie = escenario.herbivoros.iter().find(|h| h.nombre == presa)?;
+        let presa = TasasPresa::desde_herbivoro(especie, &escenario.vegetacion);
+        let lobo = TasasDepredador::desde_lobo(&escenario.lobo);
+        let ataque = tasa_ataque(escenario.espacio.as_ref(), escenario.lobo.velocidad);
+        Some(HollingII::desde_especies(&presa, &lobo, ataque))
+    }
+}
+
+impl DosPresas {
+    pub fn desde_especies(presas: [&TasasPresa; 2], lobo: &TasasDepredador, ataque: f64) -> Self {
+        DosPresas {
+            crecimiento: presas.map(|p| p.crecimiento),
+            capacidad: presas.map(|p| p.capacidad),
+            ataque: [ataque; 2],
+            manipulacion: presas.map(|p| lobo.manipulacion(p)),
+            conversion: presas.map(|p| lobo.conversion(p)),
+            mortalidad: lobo.mortalidad,
+        }
+    }
+
+    /// Modelo de media de campo con los dos primeros herbívoros del escenario.
+    pub fn desde_escenario(escenario: &Escenario) -> Option<Self> {
+        let [h1, h2] = escenario.herbivoros.get(..2)? else { return None };
+        let presas = [h1, h2].map(|h| TasasPresa::desde_herbivoro(h, &escenario.vegetacion));
+        let lobo = TasasDepredador::desde_lobo(&escenario.lobo);
+        let ataque = tasa_ataque(escenario.espacio.as_ref(), escenario.lobo.velocidad);
+        Some(DosPresas::desde_especies([&presas[0], &presas[1]], &lobo, ataque))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cerca(a: f64, b: f64, tolerancia: f64) -> bool {
+        (a - b).abs() <= tolerancia * b.abs().max(1.0)
+    }
+
+    // Ordena por parte real y luego imaginaria, para comparar con lo esperado.
+    fn ordenados(mut l: Vec<Complejo>) -> Vec<Complejo> {
+        l.sort_by(|a, b| a.re.total_cmp(&b.re).then(a.im.total_cmp(&b.im)));
+        l
+    }
+
+    const LV: LotkaVolterra = LotkaVolterra { crecimiento: 0.5, ataque: 0.02, conversion: 0.1, mortalidad: 0.3 };
+
+    // Integral primera de Lotka-Volterra: constante sobre cada órbita.
+    fn invariante(lv: &LotkaVolterra, [n, p]: [f64; 2]) -> f64 {
+        lv.conversion * lv.ataque * n - lv.mortalidad * n.ln() + lv.ataque * p - lv.crecimiento * p.ln()
+    }
+
+    #[test]
+    fn rk4_conserva_la_integral_de_lotka_volterra() {
+        let inicial = [100.0, 10.0];
+        let v0 = invariante(&LV, inicial);
+        let mut x = inicial;
+        for _ in 0..20_000 {
+            x = rk4_paso(&LV, &x, 0.01);
+        }
+        assert!(cerca(invariante(&LV, x), v0, 1e-8), "{} vs {}", invariante(&LV, x), v0);
+    }
+
+    #[test]
+    fn autovalores_de_matrices_conocidas() {
+        let l = ordenados(autovalores(&[[2.0, 1.0], [1.0, 2.0]]));
+        assert!(cerca(l[0].re, 1.0, 1e-12) && cerca(l[1].re, 3.0, 1e-12));
+        assert!(l.iter().all(|z| z.im == 0.0));
+
+        // Rotación de 90°: ±i.
+        let l = ordenados(autovalores(&[[0.0, -1.0], [1.0, 0.0]]));
+        assert!(l.iter().all(|z| z.re.abs() < 1e-12));
+        assert!(cerca(l[0].im, -1.0, 1e-12) && cerca(l[1].im, 1.0, 1e-12));
+
+        // Triangular: los autovalores son la diagonal.
+        let l = ordenados(autovalores(&[[1.0, 4.0, -2.0], [0.0, -3.0, 5.0], [0.0, 0.0, 2.0]]));
+        for (z, esperado) in l.iter().zip([-3.0, 1.0, 2.0]) {
+            assert!(cerca(z.re, esperado, 1e-10) && z.im == 0.0, "{:?}", l);
+        }
+    }
+
+    #[test]
+    fn lotka_volterra_tiene_una_silla_y_un_centro() {
+        let puntos = analizar(&LV);
+        assert_eq!(puntos.len(), 2);
+        assert_eq!(puntos[0].estabilidad, Estabilidad::Silla);
+        // El centro está en (m/(e·a), r/a) con autovalores ±i·√(r·m).
+        let centro = &puntos[1];
+        assert!(cerca(centro.estado[0], 150.0, 1e-12) && cerca(centro.estado[1], 25.0, 1e-12));
+        assert_eq!(centro.estabilidad, Estabilidad::Neutral);
+        let frecuencia = (LV.crecimiento * LV.mortalidad).sqrt();
+        assert!(centro.autovalores.iter().all(|z| cerca(z.im.abs(), frecuencia, 1e-6)));
+    }
+
+    #[test]
+    fn sin_crecimiento_de_la_presa_no_hay_punto_con_lobos() {
+        let no_negativos = |puntos: &[[f64; 2]]| puntos.iter().flatten().all(|&v| v >= 0.0);
+        let logistica = PresaLogistica { crecimiento: -0.1, capacidad: 500.0, ataque: 0.01, conversion: 0.1, mortalidad: 0.2 };
+        let puntos = logistica.puntos_fijos();
+        assert!(no_negativos(&puntos) && puntos.len() == 2, "{:?}", puntos);
+
+        let holling = HollingII { crecimiento: -0.1, capacidad: 500.0, ataque: 0.01, manipulacion: 0.1, conversion: 0.1, mortalidad: 0.2 };
+        let puntos = holling.puntos_fijos();
+        assert!(no_negativos(&puntos) && puntos.len() == 2, "{:?}", puntos);
+
+        let dos = DosPresas {
+            crecimiento: [-0.1, -0.2],
+            capacidad: [500.0, 300.0],
+            ataque: [0.01, 0.01],
+            manipulacion: [0.1, 0.05],
+            conversion: [0.1, 0.08],
+            mortalidad: 0.2,
+        };
+        assert!(dos.puntos_fijos().iter().flatten().all(|&v| v >= 0.0));
+    }
+
+    #[test]
+    fn los_puntos_fijos_de_dos_presas_anulan_la_derivada() {
+        let sistema = DosPresas {
+            crecimiento: [0.4, 0.6],
+            capacidad: [500.0, 300.0],
+            ataque: [0.01, 0.01],
+            manipulacion: [0.1, 0.05],
+            conversion: [0.1, 0.08],
+            mortalidad: 0.2,
+        };
+        let puntos = sistema.puntos_fijos();
+        assert!(puntos.iter().any(|x| x.iter().all(|&v| v > 0.0)), "sin coexistencia: {:?}", puntos);
+        for x in puntos {
+            assert!(sistema.derivada(&x).iter().all(|d| d.abs() < 1e-9), "{:?}", x);
+        }
+    }
+}
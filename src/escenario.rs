@@ -0,0 +1,520 @@
+use crate::apareamiento::SistemaApareamiento;
+use crate::calendario::{Calendario, Estacion};
+use crate::caza::{Estrategia, ExitoCaza};
+use crate::argumentos::PoblacionInicial;
+use crate::espacio::Espacio;
+use crate::herbivoro::{Herbivoro, ParametrosHerbivoro};
+use crate::lobo::{Lobo, ParametrosLobo};
+use crate::modelo::Crecimiento;
+use crate::organismo::Sexo;
+use crate::poblacion::Habitante;
+use crate::reproduccion::Ciclo;
+use crate::respuesta::Respuesta;
+use crate::simulador::Simulador;
+use crate::vegetacion::{ParametrosVegetacion, Vegetacion};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Todo lo necesario para arrancar una corrida: las especies herbívoras,
+/// el lobo, el pastizal, la población inicial de cada especie (por nombre) y,
+/// opcionalmente, semilla, duración, una grilla espacial y un calendario.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Escenario {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub semilla: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dias: Option<u32>,
+    pub poblacion: BTreeMap<String, PoblacionInicial>,
+    pub herbivoros: Vec<ParametrosHerbivoro>,
+    pub lobo: ParametrosLobo,
+    pub vegetacion: ParametrosVegetacion,
+    /// Sin `[espacio]` el mundo no tiene geografía: todos se cruzan con todos.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub espacio: Option<Espacio>,
+    /// Sin `[calendario]` los días no tienen fecha y las tasas no cambian con las estaciones.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calendario: Option<Calendario>,
+}
+
+impl Default for Escenario {
+    // Los valores con los que se diseñó el simulador.
+    fn default() -> Self {
+        Escenario {
+            semilla: None,
+            dias: None,
+            poblacion: BTreeMap::from([
+                ("Cabra".to_string(), PoblacionInicial { aleatorios: 100, ..Default::default() }),
+                ("Conejo".to_string(), PoblacionInicial { aleatorios: 10, ..Default::default() }),
+                ("Lobo".to_string(), PoblacionInicial { aleatorios: 1, ..Default::default() }),
+            ]),
+            herbivoros: vec![ParametrosHerbivoro::cabra(), ParametrosHerbivoro::conejo()],
+            lobo: ParametrosLobo::default(),
+            vegetacion: ParametrosVegetacion::default(),
+            espacio: None,
+            calendario: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ErrorEscenario {
+    /// No se pudo leer el archivo.
+    Lectura(io::Error),
+    /// El archivo no es TOML/JSON válido o le faltan campos.
+    Formato(String),
+    /// Los valores están, pero no tienen sentido.
+    Invalido { campo: String, motivo: &'static str },
+}
+
+impl fmt::Display for ErrorEscenario {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorEscenario::Lectura(e) => write!(f, "no se pudo leer el escenario: {}", e),
+            ErrorEscenario::Formato(e) => write!(f, "escenario mal formado: {}", e),
+            ErrorEscenario::Invalido { campo, motivo } => write!(f, "escenario inválido: '{}' {}", campo, motivo),
+        }
+    }
+}
+
+impl std::error::Error for ErrorEscenario {}
+
+impl Escenario {
+    /// Lee un escenario TOML o JSON según la extensión del archivo.
+    pub fn cargar(ruta: impl AsRef<Path>) -> Result<Self, ErrorEscenario> {
+        let ruta = ruta.as_ref();
+        let texto = fs::read_to_string(ruta).map_err(ErrorEscenario::Lectura)?;
+        match ruta.extension().and_then(|e| e.to_str()) {
+            Some("json") => Self::desde_json(&texto),
+            _ => Self::desde_toml(&texto),
+        }
+    }
+
+    pub fn desde_toml(texto: &str) -> Result<Self, ErrorEscenario> {
+        let escenario: Escenario = toml::from_str(texto).map_err(|e| ErrorEscenario::Formato(e.to_string()))?;
+        escenario.validar()?;
+        Ok(escenario)
+    }
+
+    pub fn desde_json(texto: &str) -> Result<Self, ErrorEscenario> {
+        let escenario: Escenario = serde_json::from_str(texto).map_err(|e| ErrorEscenario::Formato(e.to_string()))?;
+        escenario.validar()?;
+        Ok(escenario)
+    }
+
+    pub fn a_toml(&self) -> String {
+        toml::to_string_pretty(self).expect("el escenario siempre se puede serializar")
+    }
+
+    pub fn validar(&self) -> Result<(), ErrorEscenario> {
+        let mut nombres = BTreeSet::new();
+        for (i, especie) in self.herbivoros.iter().enumerate() {
+            let seccion = format!("herbivoros[{}]", i);
+            if especie.nombre.trim().is_empty() {
+                return Err(invalido(&seccion, "nombre", "no puede estar vacío"));
+            }
+            if especie.nombre == "Lobo" || !nombres.insert(especie.nombre.as_str()) {
+                return Err(invalido(&seccion, "nombre", "ya está usado por otra especie"));
+            }
+            validar_herbivoro(especie, &seccion)?;
+            if especie.temporada_cria.is_some() && self.calendario.is_none() {
+                return Err(invalido(&seccion, "temporada_cria", "necesita la sección [calendario]"));
+            }
+        }
+        validar_lobo(&self.lobo)?;
+        if self.lobo.temporada_cria.is_some() && self.calendario.is_none() {
+            return Err(invalido("lobo", "temporada_cria", "necesita la sección [calendario]"));
+        }
+        validar_vegetacion(&self.vegetacion)?;
+        if let Some(espacio) = &self.espacio {
+            validar_espacio(espacio)?;
+        }
+        if let Some(calendario) = &self.calendario {
+            validar_calendario(calendario)?;
+        }
+        for especie in self.poblacion.keys() {
+            if especie != "Lobo" && !nombres.contains(especie.as_str()) {
+                return Err(invalido("poblacion", especie, "no es una especie definida en el escenario"));
+            }
+        }
+        if self.dias == Some(0) {
+            return Err(invalido("", "dias", "debe ser mayor que 0"));
+        }
+        Ok(())
+    }
+
+    /// Crea el simulador con la biología y la población de este escenario.
+    /// La semilla explícita tiene prioridad sobre la del escenario.
+    pub fn crear_simulador(&self, semilla: Option<u64>) -> Simulador {
+        let mut sim = match semilla.or(self.semilla) {
+            Some(s) => Simulador::con_semilla(s),
+            None => Simulador::new(),
+        };
+        sim.vegetacion = Vegetacion::new(self.vegetacion);
+        if let Some(espacio) = self.espacio {
+            sim = sim.con_espacio(espacio);
+        }
+        if let Some(calendario) = self.calendario {
+            sim = sim.con_calendario(calendario);
+        }
+
+        let cantidad = |especie: &str| self.poblacion.get(especie).copied().unwrap_or_default();
+        for parametros in &self.herbivoros {
+            let especie = Arc::new(parametros.clone());
+            poblar(&mut sim, cantidad(&parametros.nombre), |sexo| Herbivoro::new(Arc::clone(&especie), sexo));
+        }
+        poblar(&mut sim, cantidad("Lobo"), |sexo| Lobo::con_parametros(&self.lobo, sexo));
+        sim
+    }
+}
+
+// Agrega la población inicial de una especie: primero los de sexo aleatorio,
+// luego los machos y hembras pedidos. Con espacio, cada uno en una celda al azar.
+fn poblar<T: Habitante>(
+    sim: &mut Simulador,
+    cantidad: PoblacionInicial,
+    crear: impl Fn(Sexo) -> T,
+) {
+    let ubicar = |sim: &mut Simulador, mut org: T| {
+        if let Some(espacio) = sim.espacio {
+            *org.posicion_mut() = espacio.aleatoria(&mut sim.rng);
+        }
+        sim.agregar(org);
+    };
+    for _ in 0..cantidad.aleatorios {
+        let sexo = Sexo::aleatorio(&mut sim.rng);
+        ubicar(sim, crear(sexo));
+    }
+    for _ in 0..cantidad.machos {
+        ubicar(sim, crear(Sexo::Macho));
+    }
+    for _ in 0..cantidad.hembras {
+        ubicar(sim, crear(Sexo::Hembra));
+    }
+}
+
+fn invalido(seccion: &str, campo: &str, motivo: &'static str) -> ErrorEscenario {
+    let campo = if seccion.is_empty() { campo.to_string() } else { format!("{}.{}", seccion, campo) };
+    ErrorEscenario::Invalido { campo, motivo }
+}
+
+fn validar_probabilidad(valor: f64, seccion: &str, campo: &str) -> Result<(), ErrorEscenario> {
+    if (0.0..=1.0).contains(&valor) {
+        Ok(())
+    } else {
+        Err(invalido(seccion, campo, "debe ser una probabilidad entre 0 y 1"))
+    }
+}
+
+fn validar_crecimiento(c: &Crecimiento, seccion: &str) -> Result<(), ErrorEscenario> {
+    let (modelo, peso_maximo, tasa, desplazamiento) = match *c {
+        Crecimiento::Gompertz(g) => ("gompertz", g.peso_maximo, g.tasa, ("inflexion", g.inflexion)),
+        Crecimiento::Logistica(l) => ("logistica", l.peso_maximo, l.tasa, ("inflexion", l.inflexion)),
+        Crecimiento::VonBertalanffy(v) => ("von_bertalanffy", v.peso_maximo, v.tasa, ("edad_cero", v.edad_cero)),
+        Crecimiento::Richards(r) => ("richards", r.peso_maximo, r.tasa, ("inflexion", r.inflexion)),
+    };
+    let campo = |nombre: &str| format!("crecimiento.{}.{}", modelo, nombre);
+    if !(peso_maximo.is_finite() && peso_maximo > 0.0) {
+        return Err(invalido(seccion, &campo("peso_maximo"), "debe ser mayor que 0"));
+    }
+    if !(tasa.is_finite() && tasa > 0.0) {
+        return Err(invalido(seccion, &campo("tasa"), "debe ser mayor que 0"));
+    }
+    if !desplazamiento.1.is_finite() {
+        return Err(invalido(seccion, &campo(desplazamiento.0), "debe ser un número finito"));
+    }
+    if let Crecimiento::Richards(r) = c
+        && !(r.forma.is_finite() && r.forma > 0.0) {
+        return Err(invalido(seccion, &campo("forma"), "debe ser mayor que 0"));
+    }
+    Ok(())
+}
+
+fn validar_lobo(lobo: &ParametrosLobo) -> Result<(), ErrorEscenario> {
+    let seccion = "lobo";
+    validar_crecimiento(&lobo.crecimiento, seccion)?;
+    validar_probabilidad(lobo.probabilidad_cria, seccion, "probabilidad_cria")?;
+    for (campo, valor) in [
+        ("nivel_minimo", lobo.nivel_minimo),
+        ("nivel_optimo", lobo.nivel_optimo),
+        ("reserva_maxima", lobo.reserva_maxima),
+        ("reserva_minima", lobo.reserva_minima),
+        ("reserva_inicial", lobo.reserva_inicial),
+        ("reserva_cria", lobo.reserva_cria),
+    ] {
+        if !valor.is_finite() || valor < 0.0 {
+            return Err(invalido(seccion, campo, "debe ser un número no negativo"));
+        }
+    }
+    if lobo.nivel_minimo > lobo.nivel_optimo {
+        return Err(invalido(seccion, "nivel_optimo", "no puede ser menor que nivel_minimo"));
+    }
+    if lobo.reserva_minima >= lobo.reserva_maxima {
+        return Err(invalido(seccion, "reserva_minima", "debe ser menor que reserva_maxima"));
+    }
+    if lobo.reserva_cria <= 0.0 {
+        return Err(invalido(seccion, "reserva_cria", "debe ser mayor que 0"));
+    }
+    if lobo.camada_maxima == 0 {
+        return Err(invalido(seccion, "camada_maxima", "debe ser al menos 1"));
+    }
+    validar_estrategia(&lobo.estrategia, seccion)?;
+    if let Some(ciclo) = &lobo.ciclo {
+        validar_ciclo(ciclo, seccion)?;
+    }
+    if let Some(exito) = &lobo.exito_caza {
+        validar_exito_caza(exito, seccion)?;
+    }
+    match &lobo.respuesta {
+        Some(respuesta) => validar_respuesta(respuesta, seccion),
+        None => Ok(()),
+    }
+}
+
+fn validar_respuesta(r: &Respuesta, seccion: &str) -> Result<(), ErrorEscenario> {
+    let (modelo, ataque, manipulacion) = match *r {
+        Respuesta::HollingI(h) => ("holling_i", h.ataque, 0.0),
+        Respuesta::HollingII(h) => ("holling_ii", h.ataque, h.manipulacion),
+        Respuesta::HollingIII(h) => ("holling_iii", h.ataque, h.manipulacion),
+        Respuesta::BeddingtonDeAngelis(b) => ("beddington_de_angelis", b.ataque, b.manipulacion),
+    };
+    let campo = |nombre: &str| format!("respuesta.{}.{}", modelo, nombre);
+    if !(ataque.is_finite() && ataque > 0.0) {
+        return Err(invalido(seccion, &campo("ataque"), "debe ser mayor que 0"));
+    }
+    if !(manipulacion.is_finite() && manipulacion >= 0.0) {
+        return Err(invalido(seccion, &campo("manipulacion"), "debe ser un número no negativo"));
+    }
+    if let Respuesta::HollingI(h) = r
+        && h.maximo.is_some_and(|m| !(m.is_finite() && m > 0.0)) {
+        return Err(invalido(seccion, &campo("maximo"), "debe ser mayor que 0"));
+    }
+    if let Respuesta::BeddingtonDeAngelis(b) = r
+        && !(b.interferencia.is_finite() && b.interferencia >= 0.0) {
+        return Err(invalido(seccion, &campo("interferencia"), "debe ser un número no negativo"));
+    }
+    Ok(())
+}
+
+fn validar_exito_caza(exito: &ExitoCaza, seccion: &str) -> Result<(), ErrorEscenario> {
+    let campo = |nombre: &str| format!("exito_caza.{}", nombre);
+    validar_probabilidad(exito.base, seccion, &campo("base"))?;
+    if !(exito.efecto_peso.is_finite() && exito.efecto_peso >= 0.0) {
+        return Err(invalido(seccion, &campo("efecto_peso"), "debe ser un número no negativo"));
+    }
+    for (nombre, valor) in [("vulnerabilidad_edad", exito.vulnerabilidad_edad), ("efecto_hambre", exito.efecto_hambre)] {
+        if !valor.is_finite() {
+            return Err(invalido(seccion, &campo(nombre), "debe ser un número finito"));
+        }
+    }
+    if !(exito.costo_fallo.is_finite() && exito.costo_fallo >= 0.0) {
+        return Err(invalido(seccion, &campo("costo_fallo"), "debe ser un número no negativo"));
+    }
+    Ok(())
+}
+
+fn validar_estrategia(e: &Estrategia, seccion: &str) -> Result<(), ErrorEscenario> {
+    let Estrategia::DietaOptima(d) = e else { return Ok(()) };
+    let campo = |nombre: &str| format!("estrategia.dieta_optima.{}", nombre);
+    if !(d.manipulacion.is_finite() && d.manipulacion > 0.0) {
+        return Err(invalido(seccion, &campo("manipulacion"), "debe ser mayor que 0"));
+    }
+    if !(d.manipulacion_por_kg.is_finite() && d.manipulacion_por_kg >= 0.0) {
+        return Err(invalido(seccion, &campo("manipulacion_por_kg"), "debe ser un número no negativo"));
+    }
+    if !(d.encuentro.is_finite() && d.encuentro > 0.0) {
+        return Err(invalido(seccion, &campo("encuentro"), "debe ser mayor que 0"));
+    }
+    Ok(())
+}
+
+fn validar_herbivoro(especie: &ParametrosHerbivoro, seccion: &str) -> Result<(), ErrorEscenario> {
+    validar_crecimiento(&especie.crecimiento, seccion)?;
+    validar_probabilidad(especie.mortalidad_diaria, seccion, "mortalidad_diaria")?;
+    validar_probabilidad(especie.probabilidad_parto, seccion, "probabilidad_parto")?;
+    validar_probabilidad(especie.mortalidad_hambre, seccion, "mortalidad_hambre")?;
+    if !especie.ingesta_diaria.is_finite() || especie.ingesta_diaria < 0.0 {
+        return Err(invalido(seccion, "ingesta_diaria", "debe ser un número no negativo"));
+    }
+    if especie.edad_maxima == 0 {
+        return Err(invalido(seccion, "edad_maxima", "debe ser mayor que 0"));
+    }
+    if especie.edad_madurez >= especie.edad_maxima {
+        return Err(invalido(seccion, "edad_madurez", "debe ser menor que edad_maxima"));
+    }
+    if especie.camada_minima == 0 {
+        return Err(invalido(seccion, "camada_minima", "debe ser al menos 1"));
+    }
+    if especie.camada_minima > especie.camada_maxima {
+        return Err(invalido(seccion, "camada_maxima", "no puede ser menor que camada_minima"));
+    }
+    if let SistemaApareamiento::Poliginia { hembras_por_macho: 0 } = especie.apareamiento {
+        return Err(invalido(seccion, "apareamiento.poliginia.hembras_por_macho", "debe ser al menos 1"));
+    }
+    match &especie.ciclo {
+        Some(ciclo) => validar_ciclo(ciclo, seccion),
+        None => Ok(()),
+    }
+}
+
+fn validar_ciclo(c: &Ciclo, seccion: &str) -> Result<(), ErrorEscenario> {
+    validar_probabilidad(c.concepcion, seccion, "ciclo.concepcion")?;
+    if c.gestacion == 0 {
+        return Err(invalido(seccion, "ciclo.gestacion", "debe ser mayor que 0"));
+    }
+    if c.intervalo_partos < c.gestacion {
+        return Err(invalido(seccion, "ciclo.intervalo_partos", "no puede ser menor que la gestación"));
+    }
+    Ok(())
+}
+
+fn validar_vegetacion(v: &ParametrosVegetacion) -> Result<(), ErrorEscenario> {
+    let seccion = "vegetacion";
+    if !(v.capacidad.is_finite() && v.capacidad > 0.0) {
+        return Err(invalido(seccion, "capacidad", "debe ser mayor que 0"));
+    }
+    if !v.tasa_rebrote.is_finite() || v.tasa_rebrote < 0.0 {
+        return Err(invalido(seccion, "tasa_rebrote", "debe ser un número no negativo"));
+    }
+    // El rebrote logístico parte de lo que queda: desde 0 no vuelve a crecer.
+    if !(v.remanente > 0.0 && v.remanente < v.capacidad) {
+        return Err(invalido(seccion, "remanente", "debe ser mayor que 0 y menor que la capacidad"));
+    }
+    if !(v.biomasa_inicial > 0.0 && v.biomasa_inicial <= v.capacidad) {
+        return Err(invalido(seccion, "biomasa_inicial", "debe ser mayor que 0 y no pasar la capacidad"));
+    }
+    Ok(())
+}
+
+fn validar_calendario(c: &Calendario) -> Result<(), ErrorEscenario> {
+    for estacion in [Estacion::Primavera, Estacion::Verano, Estacion::Otono, Estacion::Invierno] {
+        let f = c.estaciones.de(estacion);
+        for (nombre, valor) in [("rebrote", f.rebrote), ("mortalidad", f.mortalidad), ("consumo_lobo", f.consumo_lobo)] {
+            if !(valor.is_finite() && valor >= 0.0) {
+                let campo = format!("estaciones.{}.{}", estacion, nombre);
+                return Err(invalido("calendario", &campo, "debe ser un número no negativo"));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn validar_espacio(e: &Espacio) -> Result<(), ErrorEscenario> {
+    if e.ancho == 0 {
+        return Err(invalido("espacio", "ancho", "debe ser mayor que 0"));
+    }
+    if e.alto == 0 {
+        return Err(invalido("espacio", "alto", "debe ser mayor que 0"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendario::{MesDia, Temporada};
+    use crate::modelo::Gompertz;
+    use crate::respuesta::HollingII;
+
+    // El campo que el validador rechaza, o None si acepta el escenario.
+    fn rechazado(escenario: &Escenario) -> Option<String> {
+        match escenario.validar() {
+            Ok(()) => None,
+            Err(ErrorEscenario::Invalido { campo, .. }) => Some(campo),
+            Err(e) => panic!("error inesperado: {}", e),
+        }
+    }
+
+    #[test]
+    fn cada_herbivoro_dice_como_se_mueve() {
+        let texto = include_str!("../escenarios/por_defecto.toml");
+        assert_eq!(Escenario::desde_toml(texto).unwrap(), Escenario { dias: Some(300), ..Escenario::default() });
+        let sin_movimiento = texto.replacen("movimiento = \"hacia_comida\"", "", 1);
+        assert!(matches!(Escenario::desde_toml(&sin_movimiento), Err(ErrorEscenario::Formato(e)) if e.contains("movimiento")));
+    }
+
+    #[test]
+    fn el_pasto_necesita_remanente_y_biomasa_inicial() {
+        let mut escenario = Escenario::default();
+        escenario.vegetacion.remanente = 0.0;
+        assert_eq!(rechazado(&escenario).as_deref(), Some("vegetacion.remanente"));
+
+        let mut escenario = Escenario::default();
+        escenario.vegetacion.biomasa_inicial = 0.0;
+        assert_eq!(rechazado(&escenario).as_deref(), Some("vegetacion.biomasa_inicial"));
+    }
+
+    #[test]
+    fn rechaza_valores_sin_sentido_nombrando_el_campo() {
+        type Cambio = fn(&mut Escenario);
+        let casos: Vec<(&str, Cambio)> = vec![
+            ("herbivoros[0].nombre", |e| e.herbivoros[0].nombre = " ".to_string()),
+            ("herbivoros[1].nombre", |e| e.herbivoros[1].nombre = "Cabra".to_string()),
+            ("herbivoros[1].nombre", |e| e.herbivoros[1].nombre = "Lobo".to_string()),
+            ("herbivoros[1].mortalidad_diaria", |e| e.herbivoros[1].mortalidad_diaria = 1.5),
+            ("herbivoros[0].edad_madurez", |e| e.herbivoros[0].edad_madurez = e.herbivoros[0].edad_maxima),
+            ("herbivoros[0].camada_maxima", |e| e.herbivoros[0].camada_maxima = 0),
+            ("herbivoros[0].crecimiento.gompertz.tasa", |e| {
+                e.herbivoros[0].crecimiento = Gompertz { peso_maximo: 60.0, tasa: 0.0, inflexion: 150.0 }.into()
+            }),
+            ("herbivoros[0].ciclo.intervalo_partos", |e| {
+                e.herbivoros[0].ciclo = Some(Ciclo { concepcion: 0.5, gestacion: 150, intervalo_partos: 100 })
+            }),
+            ("herbivoros[0].apareamiento.poliginia.hembras_por_macho", |e| {
+                e.herbivoros[0].apareamiento = SistemaApareamiento::Poliginia { hembras_por_macho: 0 }
+            }),
+            ("herbivoros[0].temporada_cria", |e| {
+                let dia = MesDia { mes: 3, dia: 1 };
+                e.herbivoros[0].temporada_cria = Some(Temporada { desde: dia, hasta: dia })
+            }),
+            ("lobo.nivel_optimo", |e| e.lobo.nivel_optimo = e.lobo.nivel_minimo / 2.0),
+            ("lobo.reserva_minima", |e| e.lobo.reserva_minima = e.lobo.reserva_maxima),
+            ("lobo.reserva_inicial", |e| e.lobo.reserva_inicial = f64::NAN),
+            ("lobo.exito_caza.base", |e| {
+                e.lobo.exito_caza = Some(ExitoCaza { base: 2.0, efecto_peso: 0.0, vulnerabilidad_edad: 0.0, efecto_hambre: 0.0, costo_fallo: 1.0 })
+            }),
+            ("lobo.respuesta.holling_ii.ataque", |e| {
+                e.lobo.respuesta = Some(Respuesta::HollingII(HollingII { ataque: 0.0, manipulacion: 1.0 }))
+            }),
+            ("vegetacion.capacidad", |e| e.vegetacion.capacidad = -1.0),
+            ("espacio.ancho", |e| e.espacio = Some(Espacio { ancho: 0, ..Espacio::default() })),
+            ("poblacion.Oveja", |e| {
+                e.poblacion.insert("Oveja".to_string(), PoblacionInicial::default());
+            }),
+            ("dias", |e| e.dias = Some(0)),
+        ];
+        assert_eq!(rechazado(&Escenario::default()), None);
+        for (campo, cambiar) in casos {
+            let mut escenario = Escenario::default();
+            cambiar(&mut escenario);
+            assert_eq!(rechazado(&escenario).as_deref(), Some(campo));
+        }
+    }
+
+    #[test]
+    fn distingue_archivos_ilegibles_mal_formados_e_invalidos() {
+        assert!(matches!(Escenario::cargar("no/existe.toml"), Err(ErrorEscenario::Lectura(_))));
+        let texto = include_str!("../escenarios/por_defecto.toml");
+        assert!(matches!(Escenario::desde_toml("dias = "), Err(ErrorEscenario::Formato(_))));
+        assert!(matches!(Escenario::desde_json("{\"dias\": \"treinta\"}"), Err(ErrorEscenario::Formato(_))));
+        // Un campo mal escrito no se ignora en silencio.
+        let error = Escenario::desde_toml(&texto.replacen("dias = 300", "dia = 300", 1)).unwrap_err();
+        assert!(matches!(&error, ErrorEscenario::Formato(e) if e.contains("unknown field `dia`")), "{}", error);
+
+        let error = Escenario::desde_toml(&texto.replacen("dias = 300", "dias = 0", 1)).unwrap_err();
+        assert_eq!(error.to_string(), "escenario inválido: 'dias' debe ser mayor que 0");
+    }
+
+    #[test]
+    fn toml_y_json_describen_el_mismo_escenario() {
+        let escenario = Escenario { dias: Some(50), semilla: Some(3), espacio: Some(Espacio::default()), ..Escenario::default() };
+        assert_eq!(Escenario::desde_toml(&escenario.a_toml()).unwrap(), escenario);
+        let json = serde_json::to_string(&escenario).unwrap();
+        assert_eq!(Escenario::desde_json(&json).unwrap(), escenario);
+    }
+}
@@ -0,0 +1,188 @@
+use crate::organismo::Generador;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Celda de la grilla donde está un organismo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash, Serialize, Deserialize)]
+pub struct Posicion {
+    pub x: u32,
+    pub y: u32,
+}
+
+/// Cómo se desplaza una especie cada día.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Movimiento {
+    /// Camina al azar a una celda vecina (o se queda).
+    #[default]
+    Aleatorio,
+    /// Herbívoros: hacia la celda vecina con más pasto.
+    /// Depredadores: hacia la presa más cercana que ven.
+    HaciaComida,
+    /// Se aleja del depredador más cercano que ve; si no ve ninguno, camina al azar.
+    HuirDepredadores,
+}
+
+/// Mundo en grilla toroidal: al salir por un borde se entra por el opuesto.
+/// Las distancias se miden en celdas (un paso en diagonal cuenta como uno).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Espacio {
+    pub ancho: u32,
+    pub alto: u32,
+    /// Distancia máxima entre un depredador y la presa que caza.
+    pub radio_caza: u32,
+    /// Distancia máxima entre una hembra y el macho que la cubre.
+    pub radio_apareamiento: u32,
+    /// Hasta dónde ve cada organismo presas o depredadores.
+    pub radio_vision: u32,
+}
+
+impl Default for Espacio {
+    fn default() -> Self {
+        Espacio { ancho: 50, alto: 50, radio_caza: 1, radio_apareamiento: 3, radio_vision: 8 }
+    }
+}
+
+impl Espacio {
+    pub fn celdas(&self) -> usize {
+        self.ancho as usize * self.alto as usize
+    }
+
+    // Índice de la celda en vectores fila por fila (p. ej. el pasto).
+    pub fn celda(&self, p: Posicion) -> usize {
+        p.y as usize * self.ancho as usize + p.x as usize
+    }
+
+    pub fn aleatoria(&self, rng: &mut Generador) -> Posicion {
+        Posicion { x: rng.random_range(0..self.ancho), y: rng.random_range(0..self.alto) }
+    }
+
+    // Diferencia más corta de `a` a `b` en cada eje, teniendo en cuenta que los bordes se tocan.
+    fn delta(&self, a: Posicion, b: Posicion) -> (i64, i64) {
+        let corto = |desde: u32, hasta: u32, largo: u32| {
+            let largo = largo as i64;
+            let d = (hasta as i64 - desde as i64).rem_euclid(largo);
+            if d > largo / 2 { d - largo } else { d }
+        };
+        (corto(a.x, b.x, self.ancho), corto(a.y, b.y, self.alto))
+    }
+
+    pub fn distancia(&self, a: Posicion, b: Posicion) -> u32 {
+        let (dx, dy) = self.delta(a, b);
+        dx.unsigned_abs().max(dy.unsigned_abs()) as u32
+    }
+
+    pub fn desplazar(&self, p: Posicion, dx: i64, dy: i64) -> Posicion {
+        Posicion {
+            x: (p.x as i64 + dx).rem_euclid(self.ancho as i64) as u32,
+            y: (p.y as i64 + dy).rem_euclid(self.alto as i64) as u32,
+        }
+    }
+
+    /// La celda y sus ocho vecinas.
+    pub fn vecindario(&self, p: Posicion) -> impl Iterator<Item = Posicion> + use<> {
+        // La anterior, la misma y la siguiente en cada eje, dando la vuelta en los bordes.
+        let vecinas = |c: u32, largo: u32| {
+            [if c == 0 { largo - 1 } else { c - 1 }, c, if c + 1 >= largo { 0 } else { c + 1 }]
+        };
+        let (xs, ys) = (vecinas(p.x, self.ancho), vecinas(p.y, self.alto));
+        ys.into_iter().flat_map(move |y| xs.map(|x| Posicion { x, y }))
+    }
+
+    pub fn paso_aleatorio(&self, p: Posicion, rng: &mut Generador) -> Posicion {
+        self.desplazar(p, rng.random_range(-1..=1), rng.random_range(-1..=1))
+    }
+
+    pub fn paso_hacia(&self, p: Posicion, destino: Posicion) -> Posicion {
+        let (dx, dy) = self.delta(p, destino);
+        self.desplazar(p, dx.signum(), dy.signum())
+    }
+
+    pub fn paso_lejos(&self, p: Posicion, amenaza: Posicion) -> Posicion {
+        let (dx, dy) = self.delta(p, amenaza);
+        self.desplazar(p, -dx.signum(), -dy.signum())
+    }
+
+    /// Para cada celda, la más cercana de `fuentes` a no más de `radio` celdas.
+    /// Se arma una vez (recorriendo la grilla en anillos desde las fuentes)
+    /// y después cada consulta es directa.
+    pub fn mas_cercanas(&self, fuentes: impl IntoIterator<Item = Posicion>, radio: u32) -> Vec<Option<Posicion>> {
+        let mut cercana = vec![None; self.celdas()];
+        let mut frente = Vec::new();
+        for fuente in fuentes {
+            let celda = self.celda(fuente);
+            if cercana[celda].is_none() {
+                cercana[celda] = Some(fuente);
+                frente.push(fuente);
+            }
+        }
+        for _ in 0..radio {
+            let mut siguiente = Vec::new();
+            for p in frente {
+                let fuente = cercana[self.celda(p)];
+                for vecina in self.vecindario(p) {
+                    let celda = self.celda(vecina);
+                    if cercana[celda].is_none() {
+                        cercana[celda] = fuente;
+                        siguiente.push(vecina);
+                    }
+                }
+            }
+            if siguiente.is_empty() {
+                break;
+            }
+            frente = siguiente;
+        }
+        cercana
+    }
+
+    // Coordenadas de un eje a no más de `radio` de `centro`, cada una una sola vez
+    // (si la ventana da toda la vuelta, son todas).
+    fn ventana(centro: u32, radio: u32, largo: u32) -> impl Iterator<Item = u32> {
+        let todas = 2 * radio as u64 + 1 >= largo as u64;
+        let (desde, cuantas) = if todas { (0, largo) } else { (centro + largo - radio, 2 * radio + 1) };
+        (0..cuantas).map(move |k| (desde + k) % largo)
+    }
+}
+
+/// Índice de puntos por celda: encuentra los que están cerca de una posición
+/// mirando solo las celdas alrededor, sin recorrer todos los puntos.
+pub struct Rejilla {
+    espacio: Espacio,
+    // Los puntos de la celda `c` son `puntos[inicio[c]..inicio[c + 1]]`.
+    inicio: Vec<usize>,
+    puntos: Vec<usize>,
+}
+
+impl Rejilla {
+    /// Indexa las posiciones; cada punto se identifica por su lugar en la lista.
+    pub fn new(espacio: Espacio, posiciones: impl IntoIterator<Item = Posicion>) -> Self {
+        let celdas: Vec<usize> = posiciones.into_iter().map(|p| espacio.celda(p)).collect();
+        let mut inicio = vec![0; espacio.celdas() + 1];
+        for &c in &celdas {
+            inicio[c + 1] += 1;
+        }
+        for c in 0..espacio.celdas() {
+            inicio[c + 1] += inicio[c];
+        }
+        let mut libre = inicio.clone();
+        let mut puntos = vec![0; celdas.len()];
+        for (i, &c) in celdas.iter().enumerate() {
+            puntos[libre[c]] = i;
+            libre[c] += 1;
+        }
+        Rejilla { espacio, inicio, puntos }
+    }
+
+    /// Los puntos a no más de `radio` celdas de `p`, en ningún orden en particular.
+    pub fn cerca(&self, p: Posicion, radio: u32) -> impl Iterator<Item = usize> + '_ {
+        let e = self.espacio;
+        Espacio::ventana(p.y, radio, e.alto).flat_map(move |y| {
+            Espacio::ventana(p.x, radio, e.ancho).flat_map(move |x| {
+                let c = e.celda(Posicion { x, y });
+                self.puntos[self.inicio[c]..self.inicio[c + 1]].iter().copied()
+            })
+        })
+    }
+}
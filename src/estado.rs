@@ -0,0 +1,439 @@
+use crate::calendario::Calendario;
+use crate::escenario::{ErrorEscenario, Escenario};
+use crate::espacio::{Espacio, Posicion};
+use crate::herbivoro::{Herbivoro, ParametrosHerbivoro};
+use crate::historial::Historial;
+use crate::lobo::Lobo;
+use crate::organismo::{CausaMuerte, Generador, Identidad, Sexo};
+use crate::poblacion::TablaHerbivoros;
+use crate::reproduccion::EstadoReproductivo;
+use crate::simulador::Simulador;
+use crate::vegetacion::Vegetacion;
+use flate2::Compression;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Versión del formato de guardado. Se sube cada vez que cambia lo que se
+/// guarda, y solo se cargan archivos de esta misma versión: uno más viejo
+/// tomaría en silencio valores por defecto para lo que no tenía.
+/// La 2 agrega estrategias, éxito de caza, respuesta funcional, calendario
+/// y ciclo reproductivo.
+pub const VERSION: u32 = 2;
+
+// Cabecera del formato binario: firma y versión (u32 little endian).
+const FIRMA: &[u8; 4] = b"ECOS";
+
+/// Foto completa de una corrida: con ella se sigue exactamente donde se dejó.
+/// Se guarda en JSON legible o comprimido: la firma `ECOS`, la versión y el
+/// mismo JSON pasado por deflate. No hay un formato binario propio, así que
+/// no se puede contar con la posición de ningún campo en los bytes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Estado {
+    pub version: u32,
+    /// Último día simulado.
+    pub dia: u32,
+    pub semilla: u64,
+    /// Generador en el punto exacto de la secuencia en que quedó.
+    pub rng: Generador,
+    pub siguiente_id: u64,
+    pub vegetacion: Vegetacion,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub espacio: Option<Espacio>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calendario: Option<Calendario>,
+    /// Biología de las especies herbívoras, en el orden de sus tablas; los individuos la nombran.
+    pub especies: Vec<ParametrosHerbivoro>,
+    pub poblacion: Vec<Individuo>,
+    pub historial: Historial,
+}
+
+/// Un organismo guardado.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Individuo {
+    Herbivoro(HerbivoroGuardado),
+    Lobo(Box<Lobo>),
+}
+
+/// Un herbívoro sin su biología: `especie` es el nombre de la especie
+/// en `Estado::especies`, que se comparte entre todos sus individuos.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HerbivoroGuardado {
+    pub especie: String,
+    pub identidad: Identidad,
+    pub edad: u32,
+    pub peso: f64,
+    pub sexo: Sexo,
+    pub viva: bool,
+    pub causa_muerte: Option<CausaMuerte>,
+    pub saciedad: f64,
+    pub posicion: Posicion,
+    #[serde(default)]
+    pub reproduccion: EstadoReproductivo,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pareja: Option<u64>,
+}
+
+#[derive(Debug)]
+pub enum ErrorEstado {
+    Lectura(io::Error),
+    Formato(String),
+    /// El archivo es de otra versión del formato que la que lee este programa.
+    Version { archivo: u32, esperada: u32 },
+    /// Un herbívoro nombra una especie que no está en el archivo.
+    EspecieDesconocida(String),
+    /// Un organismo figura muerto pero sin causa de muerte.
+    MuertoSinCausa(u64),
+    /// Los parámetros guardados no pasan las validaciones de un escenario.
+    Invalido { campo: String, motivo: &'static str },
+    /// Un ID repetido, fuera de orden en su tabla o no menor que `siguiente_id`.
+    IdInvalido { id: u64, motivo: &'static str },
+}
+
+impl fmt::Display for ErrorEstado {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorEstado::Lectura(e) => write!(f, "no se pudo leer o escribir el archivo: {}", e),
+            ErrorEstado::Formato(e) => write!(f, "archivo mal formado: {}", e),
+            ErrorEstado::Version { archivo, esperada } => write!(
+                f,
+                "el archivo es de la versión {} de su formato y este programa solo lee la {}",
+                archivo, esperada
+            ),
+            ErrorEstado::EspecieDesconocida(e) => write!(f, "estado inválido: la especie '{}' no está definida", e),
+            ErrorEstado::MuertoSinCausa(id) => write!(f, "estado inválido: el organismo #{} está muerto sin causa de muerte", id),
+            ErrorEstado::Invalido { campo, motivo } => write!(f, "estado inválido: '{}' {}", campo, motivo),
+            ErrorEstado::IdInvalido { id, motivo } => write!(f, "estado inválido: el ID #{} {}", id, motivo),
+        }
+    }
+}
+
+impl std::error::Error for ErrorEstado {}
+
+impl From<io::Error> for ErrorEstado {
+    fn from(e: io::Error) -> Self {
+        ErrorEstado::Lectura(e)
+    }
+}
+
+impl From<ErrorEscenario> for ErrorEstado {
+    fn from(e: ErrorEscenario) -> Self {
+        match e {
+            ErrorEscenario::Invalido { campo, motivo } => ErrorEstado::Invalido { campo, motivo },
+            ErrorEscenario::Lectura(e) => ErrorEstado::Lectura(e),
+            ErrorEscenario::Formato(e) => ErrorEstado::Formato(e),
+        }
+    }
+}
+
+impl Estado {
+    /// Toma la foto del simulador al final del último día simulado.
+    pub fn de(sim: &Simulador) -> Self {
+        let especies = sim.poblacion.herbivoros.iter().map(|t| (*t.especie).clone()).collect();
+        let mut poblacion = Vec::with_capacity(sim.poblacion.len());
+        for h in sim.poblacion.herbivoros() {
+            poblacion.push(Individuo::Herbivoro(HerbivoroGuardado {
+                especie: h.especie.nombre.clone(),
+                identidad: h.identidad,
+                edad: h.edad,
+                peso: h.peso,
+                sexo: h.sexo,
+                viva: h.viva,
+                causa_muerte: h.causa_muerte,
+                saciedad: h.saciedad,
+                posicion: h.posicion,
+                reproduccion: h.reproduccion,
+                pareja: h.pareja,
+            }));
+        }
+        poblacion.extend(sim.poblacion.lobos.iter().cloned().map(|lobo| Individuo::Lobo(Box::new(lobo))));
+
+        Estado {
+            version: VERSION,
+            dia: sim.dia(),
+            semilla: sim.semilla,
+            rng: sim.rng.clone(),
+            siguiente_id: sim.siguiente_id,
+            vegetacion: sim.vegetacion.clone(),
+            espacio: sim.espacio,
+            calendario: sim.calendario,
+            especies,
+            poblacion,
+            historial: sim.historial.clone(),
+        }
+    }
+
+    /// Rearma el simulador tal como estaba. Los suscriptores no se guardan:
+    /// quien carga el estado vuelve a conectar los suyos. Rechaza los
+    /// parámetros que un escenario no aceptaría y los IDs repetidos o
+    /// desordenados, que harían fallar al motor a mitad de la corrida.
+    pub fn restaurar(self) -> Result<Simulador, ErrorEstado> {
+        self.validar()?;
+        let mut sim = Simulador::con_semilla(self.semilla);
+        sim.rng = self.rng;
+        sim.siguiente_id = self.siguiente_id;
+        sim.vegetacion = self.vegetacion;
+        sim.espacio = self.espacio;
+        sim.calendario = self.calendario;
+        sim.historial = self.historial;
+        sim.historial.recuperar_especies();
+        sim.poblacion.herbivoros = self.especies.into_iter()
+            .map(|e| TablaHerbivoros::new(Arc::new(e)))
+            .collect();
+
+        // Van directo a su tabla, sin pasar por `agregar`, para conservar sus IDs.
+        for individuo in self.poblacion {
+            let (vivo, causa, id) = match &individuo {
+                Individuo::Lobo(lobo) => (lobo.vivo, lobo.causa_muerte, lobo.identidad.id),
+                Individuo::Herbivoro(h) => (h.viva, h.causa_muerte, h.identidad.id),
+            };
+            if !vivo && causa.is_none() {
+                return Err(ErrorEstado::MuertoSinCausa(id));
+            }
+            match individuo {
+                Individuo::Lobo(lobo) => sim.poblacion.lobos.push(*lobo),
+                Individuo::Herbivoro(h) => {
+                    let tabla = sim.poblacion.herbivoros.iter_mut()
+                        .find(|t| t.especie.nombre == h.especie)
+                        .ok_or_else(|| ErrorEstado::EspecieDesconocida(h.especie.clone()))?;
+                    let especie = Arc::clone(&tabla.especie);
+                    tabla.individuos.push(Herbivoro {
+                        identidad: h.identidad,
+                        edad: h.edad,
+                        peso: h.peso,
+                        sexo: h.sexo,
+                        viva: h.viva,
+                        causa_muerte: h.causa_muerte,
+                        saciedad: h.saciedad,
+                        posicion: h.posicion,
+                        reproduccion: h.reproduccion,
+                        pareja: h.pareja,
+                        especie,
+                    });
+                }
+            }
+        }
+
+        // `Poblacion::buscar` busca por bisección: cada tabla tiene que estar ordenada por ID.
+        let tablas = sim.poblacion.herbivoros.iter()
+            .map(|t| t.individuos.iter().map(|h| h.identidad.id).collect::<Vec<_>>())
+            .chain([sim.poblacion.lobos.iter().map(|l| l.identidad.id).collect()]);
+        for ids in tablas {
+            if let Some(par) = ids.windows(2).find(|par| par[0] > par[1]) {
+                return Err(ErrorEstado::IdInvalido { id: par[1], motivo: "está fuera de orden en su tabla" });
+            }
+        }
+        Ok(sim)
+    }
+
+    // Lo que el motor da por hecho: parámetros que pasarían por un escenario
+    // (cada biología distinta de lobo se revisa una vez), e IDs únicos y
+    // menores que el próximo a asignar. El orden se revisa ya en las tablas.
+    fn validar(&self) -> Result<(), ErrorEstado> {
+        let mut escenario = Escenario {
+            poblacion: BTreeMap::new(),
+            herbivoros: self.especies.clone(),
+            vegetacion: self.vegetacion.parametros,
+            espacio: self.espacio,
+            calendario: self.calendario,
+            ..Escenario::default()
+        };
+        escenario.validar()?;
+        for individuo in &self.poblacion {
+            if let Individuo::Lobo(lobo) = individuo {
+                let parametros = lobo.parametros();
+                if parametros != escenario.lobo {
+                    escenario.lobo = parametros;
+                    escenario.validar()?;
+                }
+            }
+        }
+
+        let mut vistos = HashSet::with_capacity(self.poblacion.len());
+        for individuo in &self.poblacion {
+            let id = match individuo {
+                Individuo::Herbivoro(h) => h.identidad.id,
+                Individuo::Lobo(lobo) => lobo.identidad.id,
+            };
+            if !vistos.insert(id) {
+                return Err(ErrorEstado::IdInvalido { id, motivo: "está repetido" });
+            }
+            if id >= self.siguiente_id {
+                return Err(ErrorEstado::IdInvalido { id, motivo: "no es menor que siguiente_id" });
+            }
+        }
+        Ok(())
+    }
+
+    pub fn a_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("el estado siempre se puede serializar")
+    }
+
+    pub fn desde_json(texto: &str) -> Result<Self, ErrorEstado> {
+        leer_json(texto, VERSION)
+    }
+
+    pub fn a_binario(&self) -> Vec<u8> {
+        comprimir(FIRMA, VERSION, self)
+    }
+
+    pub fn desde_binario(bytes: &[u8]) -> Result<Self, ErrorEstado> {
+        leer_json(&descomprimir(FIRMA, VERSION, bytes)?, VERSION)
+    }
+
+    /// Guarda en JSON si la ruta termina en `.json` y en binario si no.
+    pub fn guardar(&self, ruta: impl AsRef<Path>) -> Result<(), ErrorEstado> {
+        escribir(ruta.as_ref(), || self.a_json(), || self.a_binario())
+    }
+
+    /// Lee un estado en cualquiera de los dos formatos (se reconoce por la firma).
+    pub fn cargar(ruta: impl AsRef<Path>) -> Result<Self, ErrorEstado> {
+        leer(ruta.as_ref(), FIRMA, VERSION)
+    }
+}
+
+// Lo que sigue lo comparten los estados y las grabaciones: JSON con un campo
+// `version`, o en binario la firma, la versión (u32 little endian) y el mismo
+// JSON comprimido con deflate.
+
+// Deserializa un JSON versionado, avisando bien si es de una versión más nueva.
+pub(crate) fn leer_json<T: DeserializeOwned>(texto: &str, version: u32) -> Result<T, ErrorEstado> {
+    #[derive(Deserialize)]
+    struct Cabecera {
+        version: u32,
+    }
+    let cabecera: Cabecera = serde_json::from_str(texto).map_err(|e| ErrorEstado::Formato(e.to_string()))?;
+    if cabecera.version != version {
+        return Err(ErrorEstado::Version { archivo: cabecera.version, esperada: version });
+    }
+    serde_json::from_str(texto).map_err(|e| ErrorEstado::Formato(e.to_string()))
+}
+
+pub(crate) fn comprimir(firma: &[u8; 4], version: u32, valor: &impl Serialize) -> Vec<u8> {
+    let mut bytes = firma.to_vec();
+    bytes.extend_from_slice(&version.to_le_bytes());
+    let mut compresor = DeflateEncoder::new(bytes, Compression::default());
+    serde_json::to_writer(&mut compresor, valor).expect("siempre se puede serializar");
+    compresor.finish().expect("escribir en memoria no falla")
+}
+
+// Comprueba firma y versión y devuelve el JSON descomprimido.
+pub(crate) fn descomprimir(firma: &[u8; 4], version_esperada: u32, bytes: &[u8]) -> Result<String, ErrorEstado> {
+    let Some(resto) = bytes.strip_prefix(firma) else {
+        return Err(ErrorEstado::Formato("firma binaria desconocida".to_string()));
+    };
+    let Some((version, comprimido)) = resto.split_first_chunk::<4>() else {
+        return Err(ErrorEstado::Formato("falta la versión".to_string()));
+    };
+    let version = u32::from_le_bytes(*version);
+    if version != version_esperada {
+        return Err(ErrorEstado::Version { archivo: version, esperada: version_esperada });
+    }
+    let mut texto = String::new();
+    DeflateDecoder::new(comprimido).read_to_string(&mut texto)
+        .map_err(|e| ErrorEstado::Formato(e.to_string()))?;
+    Ok(texto)
+}
+
+// JSON si la ruta termina en `.json`, binario si no.
+pub(crate) fn escribir(ruta: &Path, json: impl FnOnce() -> String, binario: impl FnOnce() -> Vec<u8>) -> Result<(), ErrorEstado> {
+    let mut archivo = fs::File::create(ruta)?;
+    match ruta.extension().and_then(|e| e.to_str()) {
+        Some("json") => archivo.write_all(json().as_bytes())?,
+        _ => archivo.write_all(&binario())?,
+    }
+    Ok(())
+}
+
+// Lee un archivo en cualquiera de los dos formatos (se reconoce por la firma).
+pub(crate) fn leer<T: DeserializeOwned>(ruta: &Path, firma: &[u8; 4], version: u32) -> Result<T, ErrorEstado> {
+    let bytes = fs::read(ruta)?;
+    let texto = if bytes.starts_with(firma) {
+        descomprimir(firma, version, &bytes)?
+    } else {
+        // Ni la firma esperada ni texto: es otro tipo de archivo binario.
+        String::from_utf8(bytes).map_err(|_| ErrorEstado::Formato("firma binaria desconocida".to_string()))?
+    };
+    leer_json(&texto, version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn simulador() -> Simulador {
+        let escenario = Escenario { espacio: Some(Espacio::default()), ..Escenario::default() };
+        escenario.crear_simulador(Some(11))
+    }
+
+    fn correr(sim: &mut Simulador, dias: u32) {
+        let inicio = sim.dia();
+        for dia in inicio + 1..=inicio + dias {
+            sim.simular_dia(dia);
+        }
+    }
+
+    // Corre 20 días, guarda, carga con `ida_y_vuelta` y sigue 20 más: tiene
+    // que terminar igual que la corrida sin cortes.
+    fn sigue_igual(ida_y_vuelta: impl Fn(&Estado) -> Estado) {
+        let mut seguido = simulador();
+        correr(&mut seguido, 40);
+
+        let mut cortado = simulador();
+        correr(&mut cortado, 20);
+        let mut cargado = ida_y_vuelta(&Estado::de(&cortado)).restaurar().unwrap();
+        assert_eq!(cargado.dia(), 20);
+        correr(&mut cargado, 20);
+
+        assert_eq!(Estado::de(&cargado).a_json(), Estado::de(&seguido).a_json());
+    }
+
+    #[test]
+    fn cargar_un_json_sigue_la_misma_corrida() {
+        sigue_igual(|estado| Estado::desde_json(&estado.a_json()).unwrap());
+    }
+
+    #[test]
+    fn cargar_el_comprimido_sigue_la_misma_corrida() {
+        sigue_igual(|estado| Estado::desde_binario(&estado.a_binario()).unwrap());
+    }
+
+    #[test]
+    fn rechaza_parametros_que_un_escenario_no_aceptaria() {
+        let mut estado = Estado::de(&simulador());
+        estado.especies[0].probabilidad_parto = 1.5;
+        assert!(matches!(estado.restaurar(), Err(ErrorEstado::Invalido { .. })));
+
+        let mut estado = Estado::de(&simulador());
+        for individuo in &mut estado.poblacion {
+            if let Individuo::Lobo(lobo) = individuo {
+                lobo.nivel_minimo = lobo.nivel_optimo + 1.0;
+            }
+        }
+        assert!(matches!(estado.restaurar(), Err(ErrorEstado::Invalido { .. })));
+    }
+
+    #[test]
+    fn rechaza_ids_repetidos_o_desordenados() {
+        let mut estado = Estado::de(&simulador());
+        let copia = estado.poblacion[0].clone();
+        estado.poblacion.push(copia);
+        assert!(matches!(estado.restaurar(), Err(ErrorEstado::IdInvalido { motivo: "está repetido", .. })));
+
+        let mut estado = Estado::de(&simulador());
+        estado.poblacion.swap(0, 1);
+        assert!(matches!(estado.restaurar(), Err(ErrorEstado::IdInvalido { motivo: "está fuera de orden en su tabla", .. })));
+
+        let mut estado = Estado::de(&simulador());
+        estado.siguiente_id = 1;
+        assert!(matches!(estado.restaurar(), Err(ErrorEstado::IdInvalido { .. })));
+    }
+}
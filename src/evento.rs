@@ -0,0 +1,128 @@
+use crate::organismo::CausaMuerte;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Lo que el motor de simulación le comunica al exterior.
+/// El simulador no imprime nada: emite estos eventos y cada
+/// suscriptor (consola, interfaz gráfica, análisis) decide qué hacer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Evento {
+    /// Nació una cría.
+    Nacimiento { dia: u32, id: u64, especie: String, madre: Option<u64>, padre: Option<u64> },
+    /// Murió un organismo, con su causa.
+    Muerte { dia: u32, id: u64, especie: String, edad: u32, peso: f64, causa: CausaMuerte },
+    /// Un depredador cazó una presa.
+    Caza { dia: u32, depredador_id: u64, depredador: String, presa_id: u64, presa: String, peso: f64 },
+    /// Se perdieron `crias` en gestación: murió la madre preñada o, al parir,
+    /// no tenía reserva para alimentarlas (causa: hambre).
+    CamadaPerdida { dia: u32, madre: u64, especie: String, crias: u32, causa: CausaMuerte },
+    /// Un depredador atacó una presa pero se le escapó; le costó `costo` kg de reserva.
+    Escape { dia: u32, depredador_id: u64, depredador: String, presa_id: u64, presa: String, costo: f64 },
+    /// Un depredador con respuesta funcional salió a cazar con `presas` a su
+    /// alcance y `depredadores` cazando a su lado (contándose a sí mismo);
+    /// su curva le daba `esperados` ataques.
+    Busqueda { dia: u32, id: u64, especie: String, presas: usize, depredadores: usize, esperados: f64 },
+    /// Un depredador necesitaba cazar pero no encontró presas.
+    Inanicion { dia: u32, id: u64, especie: String, reserva: f64 },
+    /// Un depredador comió de su reserva.
+    Alimentacion { dia: u32, id: u64, especie: String, consumo: f64, reserva: f64 },
+    /// Los herbívoros pastaron: pidieron `demanda` kg, comieron `consumo`
+    /// y quedó `biomasa` en el pastizal.
+    Pastoreo { dia: u32, demanda: f64, consumo: f64, biomasa: f64 },
+    /// Terminó el día; resume muertes y población restante.
+    DiaTerminado { dia: u32, muertes: usize, poblacion: usize },
+}
+
+impl Evento {
+    pub fn dia(&self) -> u32 {
+        match self {
+            Evento::Nacimiento { dia, .. }
+            | Evento::Muerte { dia, .. }
+            | Evento::Caza { dia, .. }
+            | Evento::Escape { dia, .. }
+            | Evento::CamadaPerdida { dia, .. }
+            | Evento::Busqueda { dia, .. }
+            | Evento::Inanicion { dia, .. }
+            | Evento::Alimentacion { dia, .. }
+            | Evento::Pastoreo { dia, .. }
+            | Evento::DiaTerminado { dia, .. } => *dia,
+        }
+    }
+}
+
+// Mensajes legibles, los mismos que antes se imprimían directamente.
+impl fmt::Display for Evento {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Evento::Nacimiento { id, especie, madre, padre, .. } => {
+                write!(f, "🐣 Nació {} #{}", especie, id)?;
+                if let Some(madre) = madre {
+                    write!(f, " (madre #{}", madre)?;
+                    match padre {
+                        Some(padre) => write!(f, ", padre #{})", padre)?,
+                        None => write!(f, ")")?,
+                    }
+                }
+                Ok(())
+            }
+            Evento::Muerte { id, especie, edad, peso, causa, .. } => write!(
+                f,
+                "☠️ {} #{} murió a los {} días (peso final {:.2}, causa: {})",
+                especie, id, edad, peso, causa
+            ),
+            Evento::Caza { depredador_id, depredador, presa_id, presa, peso, .. } => write!(
+                f,
+                " El {} #{} cazó un {} #{} de {:.2} kg",
+                depredador.to_lowercase(), depredador_id, presa, presa_id, peso
+            ),
+            Evento::Escape { depredador_id, depredador, presa_id, presa, costo, .. } => write!(
+                f,
+                " Se le escapó un {} #{} al {} #{} (gastó {:.2} kg)",
+                presa, presa_id, depredador.to_lowercase(), depredador_id, costo
+            ),
+            Evento::CamadaPerdida { madre, especie, crias, causa, .. } => write!(
+                f,
+                "💔 {} #{} perdió {} crías en gestación (causa: {})",
+                especie, madre, crias, causa
+            ),
+            Evento::Busqueda { id, especie, presas, depredadores, esperados, .. } => write!(
+                f,
+                " El {} #{} sale a cazar con {} presas a su alcance y {} depredadores cerca ({:.2} ataques esperados)",
+                especie.to_lowercase(), id, presas, depredadores, esperados
+            ),
+            Evento::Inanicion { id, especie, reserva, .. } => write!(
+                f,
+                " El {} #{} no encontró presas (reserva: {:.2})",
+                especie.to_lowercase(), id, reserva
+            ),
+            Evento::Alimentacion { id, especie, consumo, reserva, .. } => write!(
+                f,
+                " El {} #{} consumió {:.2} kg de su reserva (quedan {:.2})",
+                especie.to_lowercase(), id, consumo, reserva
+            ),
+            Evento::Pastoreo { demanda, consumo, biomasa, .. } => write!(
+                f,
+                "🌿 Los herbívoros comieron {:.2} de {:.2} kg de pasto (quedan {:.2})",
+                consumo, demanda, biomasa
+            ),
+            Evento::DiaTerminado { dia, muertes, poblacion } => write!(
+                f,
+                "Fin del día {}: {} muertes, {} organismos",
+                dia, muertes, poblacion
+            ),
+        }
+    }
+}
+
+/// Quien quiera enterarse de los eventos del motor.
+pub trait Suscriptor {
+    fn notificar(&mut self, evento: &Evento);
+}
+
+// Cualquier closure `FnMut(&Evento)` sirve como suscriptor.
+impl<F: FnMut(&Evento)> Suscriptor for F {
+    fn notificar(&mut self, evento: &Evento) {
+        self(evento)
+    }
+}
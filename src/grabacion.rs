@@ -0,0 +1,174 @@
+use crate::calendario::Calendario;
+use crate::estado::{self, ErrorEstado};
+use crate::evento::Evento;
+use crate::simulador::Simulador;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Versión del formato de las grabaciones; como en los estados, solo se
+/// cargan las de esta misma versión. La 2 agrega el calendario y los escapes.
+pub const VERSION: u32 = 2;
+
+const FIRMA: &[u8; 4] = b"ECOG";
+
+/// Lo que se ve de un día: alcanza para dibujarlo sin volver a simular.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fotograma {
+    pub dia: u32,
+    pub censo: BTreeMap<String, usize>,
+    /// Biomasa del pastizal (kg).
+    pub vegetacion: f64,
+    /// Reserva de comida de cada lobo vivo.
+    pub reservas: Vec<f64>,
+    /// Los ataques del día: cazas y presas que escaparon.
+    pub cazas: Vec<Evento>,
+}
+
+impl Fotograma {
+    /// Foto del simulador al final de su último día.
+    pub fn de(sim: &Simulador, cazas: Vec<Evento>) -> Self {
+        Fotograma {
+            dia: sim.dia(),
+            censo: sim.censo(),
+            vegetacion: sim.vegetacion.biomasa(),
+            reservas: sim.poblacion.lobos.iter().map(|lobo| lobo.reserva).collect(),
+            cazas,
+        }
+    }
+}
+
+/// Corrida grabada día por día para reproducirla en `ecosistema_grafico`.
+/// Se guarda como los estados: JSON, o el mismo JSON comprimido con deflate
+/// detrás de la firma `ECOG`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Grabacion {
+    pub version: u32,
+    pub semilla: u64,
+    /// Especies en el orden en que se dibujan: los herbívoros y el lobo.
+    pub especies: Vec<String>,
+    /// Para mostrar la fecha y la estación de cada día, si la corrida las tiene.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calendario: Option<Calendario>,
+    /// El primero es el punto de partida; luego uno por día simulado.
+    pub fotogramas: Vec<Fotograma>,
+}
+
+impl Grabacion {
+    /// Empieza una grabación con la foto del simulador tal como está.
+    pub fn new(sim: &Simulador) -> Self {
+        let mut especies: Vec<String> = sim.poblacion.herbivoros.iter().map(|t| t.especie.nombre.clone()).collect();
+        especies.push("Lobo".to_string());
+        Grabacion {
+            version: VERSION,
+            semilla: sim.semilla,
+            especies,
+            calendario: sim.calendario,
+            fotogramas: vec![Fotograma::de(sim, Vec::new())],
+        }
+    }
+
+    /// Agrega el día que el simulador acaba de terminar.
+    pub fn agregar(&mut self, sim: &Simulador, cazas: Vec<Evento>) {
+        self.fotogramas.push(Fotograma::de(sim, cazas));
+    }
+
+    pub fn a_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("la grabación siempre se puede serializar")
+    }
+
+    pub fn a_binario(&self) -> Vec<u8> {
+        estado::comprimir(FIRMA, VERSION, self)
+    }
+
+    /// Guarda en JSON si la ruta termina en `.json` y en binario si no.
+    pub fn guardar(&self, ruta: impl AsRef<Path>) -> Result<(), ErrorEstado> {
+        estado::escribir(ruta.as_ref(), || self.a_json(), || self.a_binario())
+    }
+
+    pub fn cargar(ruta: impl AsRef<Path>) -> Result<Self, ErrorEstado> {
+        let grabacion: Grabacion = estado::leer(ruta.as_ref(), FIRMA, VERSION)?;
+        if grabacion.fotogramas.is_empty() {
+            return Err(ErrorEstado::Formato("la grabación no tiene ningún día".to_string()));
+        }
+        Ok(grabacion)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::argumentos::PoblacionInicial;
+    use crate::calendario::Fecha;
+    use crate::escenario::Escenario;
+    use crate::lobo::ParametrosLobo;
+    use std::cell::RefCell;
+    use std::path::PathBuf;
+    use std::rc::Rc;
+
+    // Graba `dias` días de una corrida con lobos hambrientos y calendario,
+    // como lo hace `simulador --grabar`.
+    fn grabar(dias: u32) -> Grabacion {
+        let inicio = Fecha::new(2024, 2, 20).unwrap();
+        let calendario = Calendario { inicio, hemisferio: Default::default(), estaciones: Default::default() };
+        let mut escenario = Escenario {
+            lobo: ParametrosLobo { reserva_inicial: 60.0, edad_minima_presa: 0, ..Default::default() },
+            calendario: Some(calendario),
+            ..Escenario::default()
+        };
+        escenario.poblacion.insert("Lobo".to_string(), PoblacionInicial { aleatorios: 3, ..Default::default() });
+        let mut sim = escenario.crear_simulador(Some(2));
+        let eventos = Rc::new(RefCell::new(Vec::new()));
+        let buzon = Rc::clone(&eventos);
+        sim.suscribir(move |evento: &Evento| buzon.borrow_mut().push(evento.clone()));
+
+        let mut grabacion = Grabacion::new(&sim);
+        for dia in 1..=dias {
+            sim.simular_dia(dia);
+            let cazas = eventos.borrow_mut().drain(..)
+                .filter(|e| matches!(e, Evento::Caza { .. } | Evento::Escape { .. }))
+                .collect();
+            grabacion.agregar(&sim, cazas);
+        }
+        grabacion
+    }
+
+    // Un archivo en el directorio temporal, distinto para cada prueba.
+    fn temporal(nombre: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("grabacion_{}_{}", std::process::id(), nombre))
+    }
+
+    #[test]
+    fn la_grabacion_vuelve_igual_en_los_dos_formatos() {
+        let grabacion = grabar(40);
+        assert_eq!(grabacion.fotogramas.len(), 41);
+        assert_eq!(grabacion.especies, ["Cabra", "Conejo", "Lobo"]);
+        assert!(grabacion.fotogramas.iter().enumerate().all(|(i, f)| f.dia == i as u32));
+        assert!(grabacion.fotogramas.iter().any(|f| !f.cazas.is_empty()));
+
+        for nombre in ["a.json", "a.ecog"] {
+            let ruta = temporal(nombre);
+            grabacion.guardar(&ruta).unwrap();
+            let cargada = Grabacion::cargar(&ruta);
+            let _ = std::fs::remove_file(&ruta);
+            assert_eq!(cargada.unwrap(), grabacion);
+        }
+        assert!(grabacion.a_binario().starts_with(FIRMA));
+    }
+
+    #[test]
+    fn rechaza_grabaciones_vacias_o_de_otra_version() {
+        let mut grabacion = grabar(2);
+        let ruta = temporal("b.json");
+        grabacion.fotogramas.clear();
+        grabacion.guardar(&ruta).unwrap();
+        assert!(matches!(Grabacion::cargar(&ruta), Err(ErrorEstado::Formato(_))));
+
+        let mut grabacion = grabar(2);
+        grabacion.version = VERSION + 1;
+        grabacion.guardar(&ruta).unwrap();
+        let cargada = Grabacion::cargar(&ruta);
+        let _ = std::fs::remove_file(&ruta);
+        assert!(matches!(cargada, Err(ErrorEstado::Version { archivo, esperada: VERSION }) if archivo == VERSION + 1));
+    }
+}
@@ -0,0 +1,235 @@
+use crate::apareamiento::SistemaApareamiento;
+use crate::calendario::{Forzamiento, Temporada};
+use crate::espacio::{Movimiento, Posicion};
+use crate::modelo::{Crecimiento, CurvaCrecimiento, Gompertz};
+use crate::organismo::{CausaMuerte, Generador, Identidad, Organismo, Sexo};
+use crate::reproduccion::{Ciclo, EstadoReproductivo};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Definición de una especie herbívora. Cualquier herbívoro nuevo
+/// (ciervo, liebre...) se describe solo con estos parámetros.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ParametrosHerbivoro {
+    pub nombre: String,
+    pub crecimiento: Crecimiento,
+    /// Muere de vejez al llegar a esta edad (días).
+    pub edad_maxima: u32,
+    /// Probabilidad diaria de enfermarse y morir.
+    pub mortalidad_diaria: f64,
+    /// Puede reproducirse a partir de esta edad (días).
+    pub edad_madurez: u32,
+    /// Probabilidad diaria de parto de una hembra apareada (sin `ciclo`).
+    pub probabilidad_parto: f64,
+    pub camada_minima: u32,
+    pub camada_maxima: u32,
+    pub apareamiento: SistemaApareamiento,
+    /// Pasto que come por día, como fracción de su peso.
+    pub ingesta_diaria: f64,
+    /// Probabilidad diaria de morir de hambre sin comer nada;
+    /// con comida parcial se escala por lo que le faltó.
+    pub mortalidad_hambre: f64,
+    /// Solo con espacio: cómo se mueve y cuántas celdas por día. Son
+    /// obligatorios porque cada especie tiene los suyos (los presets no
+    /// coinciden) y el archivo no dice de qué preset parte.
+    pub movimiento: Movimiento,
+    pub velocidad: u32,
+    /// Con calendario: época del año en que se aparea (y, sin `ciclo`, en
+    /// que pare). Sin ella, todo el año.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temporada_cria: Option<Temporada>,
+    /// Celo, gestación y lactancia de las hembras. Sin él, una hembra
+    /// apareada pare cualquier día con `probabilidad_parto`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ciclo: Option<Ciclo>,
+}
+
+impl ParametrosHerbivoro {
+    // Preset con el comportamiento original de la cabra.
+    pub fn cabra() -> Self {
+        ParametrosHerbivoro {
+            nombre: "Cabra".to_string(),
+            crecimiento: Gompertz { peso_maximo: 60.0, tasa: 0.01, inflexion: 150.0 }.into(),
+            edad_maxima: 365 * 12,
+            mortalidad_diaria: 0.005,
+            edad_madurez: 200,
+            probabilidad_parto: 0.01,
+            camada_minima: 1,
+            camada_maxima: 2,
+            apareamiento: SistemaApareamiento::Poliginia { hembras_por_macho: 20 },
+            ingesta_diaria: 0.03,
+            mortalidad_hambre: 0.05,
+            movimiento: Movimiento::HaciaComida,
+            velocidad: 1,
+            temporada_cria: None,
+            ciclo: None,
+        }
+    }
+
+    // Preset con el comportamiento original del conejo.
+    pub fn conejo() -> Self {
+        ParametrosHerbivoro {
+            nombre: "Conejo".to_string(),
+            crecimiento: Gompertz { peso_maximo: 2.0, tasa: 0.05, inflexion: 50.0 }.into(),
+            edad_maxima: 365 * 2,
+            mortalidad_diaria: 0.01,
+            edad_madurez: 90,
+            probabilidad_parto: 0.1,
+            camada_minima: 1,
+            camada_maxima: 4,
+            apareamiento: SistemaApareamiento::Poliginia { hembras_por_macho: 10 },
+            ingesta_diaria: 0.06,
+            mortalidad_hambre: 0.1,
+            movimiento: Movimiento::HuirDepredadores,
+            velocidad: 1,
+            temporada_cria: None,
+            ciclo: None,
+        }
+    }
+}
+
+/// Un individuo de cualquier especie herbívora; su biología sale de
+/// los parámetros de la especie, compartidos por toda la corrida.
+pub struct Herbivoro {
+    pub identidad: Identidad,
+    pub edad: u32,
+    pub peso: f64,
+    pub sexo: Sexo,
+    pub viva: bool,
+    pub causa_muerte: Option<CausaMuerte>,
+    /// Fracción de la ingesta diaria que consiguió comer hoy (0 a 1).
+    pub saciedad: f64,
+    pub posicion: Posicion,
+    /// Solo cambia en las hembras de especies con ciclo reproductivo.
+    pub reproduccion: EstadoReproductivo,
+    /// En especies monógamas, el ID de su pareja mientras viva.
+    pub pareja: Option<u64>,
+    pub especie: Arc<ParametrosHerbivoro>,
+}
+
+impl Herbivoro {
+    // Crea un recién nacido (o individuo inicial) de la especie con el sexo dado.
+    pub fn new(especie: Arc<ParametrosHerbivoro>, sexo: Sexo) -> Self {
+        Herbivoro {
+            identidad: Identidad::default(),
+            edad: 0,
+            peso: especie.crecimiento.peso(0.0),
+            sexo,
+            viva: true,
+            causa_muerte: None,
+            saciedad: 1.0,
+            posicion: Posicion::default(),
+            reproduccion: EstadoReproductivo::default(),
+            pareja: None,
+            especie,
+        }
+    }
+
+    // Crea un individuo de la especie con sexo aleatorio.
+    pub fn new_random(especie: Arc<ParametrosHerbivoro>, rng: &mut Generador) -> Self {
+        Herbivoro::new(especie, Sexo::aleatorio(rng))
+    }
+
+    /// Pasto que quiere comer hoy (kg).
+    pub fn demanda(&self) -> f64 {
+        if self.viva { self.peso * self.especie.ingesta_diaria } else { 0.0 }
+    }
+
+    // Registra qué fracción de su demanda pudo comer.
+    pub fn comer(&mut self, fraccion: f64) {
+        self.saciedad = fraccion.clamp(0.0, 1.0);
+    }
+
+    /// Si puede aparearse hoy: madura y, si es hembra, en celo.
+    pub fn en_celo(&self) -> bool {
+        self.es_maduro() && self.reproduccion.en_celo()
+    }
+
+    //Solo las hembras apareadas paren (menos si pasan hambre); cada cría hereda la especie y tiene sexo 50/50.
+    // `pareja` es el ID del macho que cubrió a la hembra hoy (None si no encontró).
+    // Con ciclo reproductivo, aparearse solo la deja preñada y pare al terminar la gestación.
+    pub fn reproducirse(&mut self, pareja: Option<u64>, rng: &mut Generador) -> Vec<Herbivoro> {
+        if !self.viva { return Vec::new(); }
+        let Some(ciclo) = self.especie.ciclo else {
+            if self.es_maduro() && self.sexo == Sexo::Hembra && pareja.is_some()
+                && rng.random_bool(self.especie.probabilidad_parto * self.saciedad) {
+                let cantidad = rng.random_range(self.especie.camada_minima..=self.especie.camada_maxima);
+                return self.parir(cantidad, pareja, rng);
+            }
+            return Vec::new();
+        };
+
+        let crias = match self.reproduccion.avanzar(&ciclo) {
+            Some(parto) => self.parir(parto.camada, Some(parto.padre), rng),
+            None => Vec::new(),
+        };
+        // La camada se decide al concebir.
+        if let Some(padre) = pareja
+            && self.en_celo() && self.sexo == Sexo::Hembra
+            && rng.random_bool(ciclo.concepcion * self.saciedad) {
+            let camada = rng.random_range(self.especie.camada_minima..=self.especie.camada_maxima);
+            self.reproduccion.concebir(&ciclo, padre, camada);
+        }
+        crias
+    }
+
+    fn parir(&self, cantidad: u32, pareja: Option<u64>, rng: &mut Generador) -> Vec<Herbivoro> {
+        let mut crias = Vec::with_capacity(cantidad as usize);
+        for _ in 0..cantidad {
+            let mut cria = Herbivoro::new_random(Arc::clone(&self.especie), rng);
+            cria.identidad = Identidad::cria_de(self.identidad.id, pareja);
+            cria.posicion = self.posicion; // nace donde está la madre
+            crias.push(cria);
+        }
+        crias
+    }
+}
+
+//Implementacion del trait
+impl Organismo for Herbivoro {
+    fn envejecer(&mut self, forzamiento: &Forzamiento, rng: &mut Generador) {
+        if !self.viva { return; }
+        self.edad += 1;
+        // Bien alimentado sigue su curva; con hambre se queda atrás.
+        let objetivo = self.especie.crecimiento.peso(self.edad as f64);
+        self.peso += (objetivo - self.peso) * self.saciedad;
+
+        // Muerte por vejez
+        if self.edad >= self.especie.edad_maxima {
+            self.viva = false;
+            self.causa_muerte = Some(CausaMuerte::Vejez);
+            return;
+        }
+
+        // Probabilidad diaria de enfermarse y morir (más alta en inviernos duros)
+        if rng.random_bool((self.especie.mortalidad_diaria * forzamiento.mortalidad).min(1.0)) {
+            self.viva = false;
+            self.causa_muerte = Some(CausaMuerte::Enfermedad);
+            return;
+        }
+
+        // Sin comida suficiente puede morir de hambre
+        if self.saciedad < 1.0 && rng.random_bool(self.especie.mortalidad_hambre * (1.0 - self.saciedad)) {
+            self.viva = false;
+            self.causa_muerte = Some(CausaMuerte::Hambre);
+        }
+    }
+
+    fn peso(&self) -> f64 { self.peso }
+    fn esta_vivo(&self) -> bool { self.viva }
+    fn nombre(&self) -> &str { &self.especie.nombre }
+    fn edad(&self) -> u32 { self.edad }
+    fn sexo(&self) -> Sexo { self.sexo }
+    fn es_maduro(&self) -> bool { self.viva && self.edad > self.especie.edad_madurez }
+    fn apareamiento(&self) -> SistemaApareamiento { self.especie.apareamiento }
+    fn causa_muerte(&self) -> Option<CausaMuerte> { self.causa_muerte }
+    fn gestando(&self) -> u32 { self.reproduccion.gestando() }
+    fn identidad(&self) -> &Identidad { &self.identidad }
+    fn identidad_mut(&mut self) -> &mut Identidad { &mut self.identidad }
+    fn posicion(&self) -> Posicion { self.posicion }
+    fn posicion_mut(&mut self) -> &mut Posicion { &mut self.posicion }
+    fn movimiento(&self) -> Movimiento { self.especie.movimiento }
+    fn velocidad(&self) -> u32 { self.especie.velocidad }
+}
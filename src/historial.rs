@@ -0,0 +1,390 @@
+use crate::calendario::{Calendario, Estacion, Fecha};
+use crate::evento::Evento;
+use crate::organismo::{CausaMuerte, Organismo};
+use crate::poblacion::Poblacion;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Resumen de una especie al final de un día.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegistroEspecie {
+    pub especie: String,
+    pub individuos: usize,
+    pub nacimientos: usize,
+    pub muertes: usize,
+    /// Sin individuos no hay peso que promediar (vacío en el CSV, `null` en JSON).
+    pub peso_medio: Option<f64>,
+    pub peso_mediano: Option<f64>,
+    pub biomasa: f64,
+    /// Solo para depredadores con reserva de comida (lobos).
+    pub reserva_total: Option<f64>,
+    pub reserva_media: Option<f64>,
+    /// Solo para depredadores: ataques que terminaron en caza y los que fallaron.
+    #[serde(default)]
+    pub cazas: usize,
+    #[serde(default)]
+    pub cazas_fallidas: usize,
+    /// Hembras preñadas al final del día (solo especies con ciclo reproductivo).
+    #[serde(default)]
+    pub prenadas: usize,
+    /// Crías en gestación que se perdieron (con la madre muerta o al nacer
+    /// sin reserva que las alimente), y cuántas porque a la madre la cazaron.
+    #[serde(default)]
+    pub crias_perdidas: usize,
+    #[serde(default)]
+    pub crias_perdidas_caza: usize,
+    /// Solo para depredadores con respuesta funcional: cuántos salieron a
+    /// cazar y la suma, entre ellos, de las presas a su alcance, de los
+    /// depredadores a su lado y de los ataques que les daba la curva.
+    #[serde(default)]
+    pub busquedas: usize,
+    #[serde(default)]
+    pub presas_al_alcance: usize,
+    #[serde(default)]
+    pub depredadores_cerca: usize,
+    #[serde(default)]
+    pub ataques_esperados: f64,
+}
+
+impl RegistroEspecie {
+    /// Ataques del día, con o sin éxito.
+    pub fn intentos_caza(&self) -> usize {
+        self.cazas + self.cazas_fallidas
+    }
+}
+
+/// Todo lo registrado en un día.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegistroDia {
+    pub dia: u32,
+    /// Solo con calendario.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fecha: Option<Fecha>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estacion: Option<Estacion>,
+    /// Biomasa del pastizal al final del día (kg).
+    pub vegetacion: f64,
+    pub especies: Vec<RegistroEspecie>,
+}
+
+/// Lo que cazó un depredador en toda la corrida, con las medidas que se
+/// usan en el campo: éxito por ataque y presas por depredador y por día.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ResumenCaza {
+    pub intentos: usize,
+    pub cazas: usize,
+    /// Suma de los depredadores vivos al final de cada día.
+    pub depredadores_dia: usize,
+}
+
+impl ResumenCaza {
+    /// Fracción de los ataques que terminaron en caza.
+    pub fn exito(&self) -> f64 {
+        if self.intentos == 0 { 0.0 } else { self.cazas as f64 / self.intentos as f64 }
+    }
+
+    /// Presas por depredador y por día.
+    pub fn tasa_muerte(&self) -> f64 {
+        if self.depredadores_dia == 0 { 0.0 } else { self.cazas as f64 / self.depredadores_dia as f64 }
+    }
+}
+
+/// Serie temporal de la población, día por día y especie por especie.
+/// El simulador la alimenta con sus eventos y la cierra al final de cada día.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Historial {
+    pub dias: Vec<RegistroDia>,
+    // Conteos del día en curso, por especie.
+    #[serde(skip)]
+    nacimientos: BTreeMap<String, usize>,
+    #[serde(skip)]
+    muertes: BTreeMap<String, usize>,
+    // Ataques del día en curso, por depredador: (con éxito, fallidos).
+    #[serde(skip)]
+    ataques: BTreeMap<String, (usize, usize)>,
+    // Crías perdidas del día en curso, por especie: (todas, por depredación).
+    #[serde(skip)]
+    perdidas: BTreeMap<String, (usize, usize)>,
+    // Salidas a cazar del día en curso, por depredador con respuesta funcional.
+    #[serde(skip)]
+    busquedas: BTreeMap<String, Busquedas>,
+    // Especies vistas alguna vez: siguen apareciendo (con 0) tras extinguirse.
+    #[serde(skip)]
+    especies: BTreeSet<String>,
+}
+
+impl Historial {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Tras cargar un historial guardado, recupera las especies ya vistas
+    // (no se escriben en el JSON) para que las extintas sigan apareciendo.
+    pub(crate) fn recuperar_especies(&mut self) {
+        self.especies = self.dias.iter()
+            .flat_map(|d| d.especies.iter().map(|e| e.especie.clone()))
+            .collect();
+    }
+
+    // Acumula nacimientos, muertes, ataques y camadas perdidas del día en curso.
+    pub fn anotar(&mut self, evento: &Evento) {
+        match evento {
+            Evento::Nacimiento { especie, .. } => {
+                *self.nacimientos.entry(especie.clone()).or_default() += 1;
+            }
+            Evento::Muerte { especie, .. } => {
+                *self.muertes.entry(especie.clone()).or_default() += 1;
+            }
+            Evento::Caza { depredador, .. } => {
+                self.ataques.entry(depredador.clone()).or_default().0 += 1;
+            }
+            Evento::Escape { depredador, .. } => {
+                self.ataques.entry(depredador.clone()).or_default().1 += 1;
+            }
+            Evento::Busqueda { especie, presas, depredadores, esperados, .. } => {
+                let busquedas = self.busquedas.entry(especie.clone()).or_default();
+                busquedas.cantidad += 1;
+                busquedas.presas += presas;
+                busquedas.depredadores += depredadores;
+                busquedas.esperados += esperados;
+            }
+            Evento::CamadaPerdida { especie, crias, causa, .. } => {
+                let perdidas = self.perdidas.entry(especie.clone()).or_default();
+                perdidas.0 += *crias as usize;
+                if *causa == CausaMuerte::Depredacion {
+                    perdidas.1 += *crias as usize;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Cierra el día: toma la foto de la población y reinicia los conteos.
+    pub fn cerrar_dia(&mut self, dia: u32, poblacion: &Poblacion, vegetacion: f64, calendario: Option<&Calendario>) {
+        // Pesos de cada especie presente, sus hembras preñadas y, para los lobos, sus reservas.
+        let mut por_especie: BTreeMap<&str, Foto> = BTreeMap::new();
+        for tabla in poblacion.herbivoros.iter().filter(|t| !t.individuos.is_empty()) {
+            por_especie.insert(&tabla.especie.nombre, Foto {
+                pesos: tabla.individuos.iter().map(|h| h.peso).collect(),
+                prenadas: tabla.individuos.iter().filter(|h| h.gestando() > 0).count(),
+                reservas: None,
+            });
+        }
+        if let Some(lobo) = poblacion.lobos.first() {
+            por_especie.insert(lobo.nombre(), Foto {
+                pesos: poblacion.lobos.iter().map(|l| l.peso).collect(),
+                prenadas: poblacion.lobos.iter().filter(|l| l.gestando() > 0).count(),
+                reservas: Some(poblacion.lobos.iter().map(|l| l.reserva).collect()),
+            });
+        }
+        for especie in por_especie.keys() {
+            self.especies.insert(especie.to_string());
+        }
+        let contadas = self.nacimientos.keys().chain(self.muertes.keys()).chain(self.ataques.keys());
+        for especie in contadas.chain(self.perdidas.keys()).chain(self.busquedas.keys()) {
+            self.especies.insert(especie.clone());
+        }
+
+        let especies = self.especies.iter().map(|especie| {
+            let Foto { mut pesos, prenadas, reservas } = por_especie.remove(especie.as_str()).unwrap_or_default();
+            let individuos = pesos.len();
+            // Sumar una lista vacía da -0.0, que se escribiría como "-0.0000".
+            let biomasa = if individuos == 0 { 0.0 } else { pesos.iter().sum() };
+            let reserva_total = reservas.as_ref().map(|r| r.iter().sum::<f64>());
+            let (cazas, cazas_fallidas) = self.ataques.get(especie).copied().unwrap_or_default();
+            let (crias_perdidas, crias_perdidas_caza) = self.perdidas.get(especie).copied().unwrap_or_default();
+            let busquedas = self.busquedas.get(especie).copied().unwrap_or_default();
+
+            RegistroEspecie {
+                especie: especie.clone(),
+                individuos,
+                nacimientos: self.nacimientos.get(especie).copied().unwrap_or(0),
+                muertes: self.muertes.get(especie).copied().unwrap_or(0),
+                peso_medio: (individuos > 0).then(|| biomasa / individuos as f64),
+                peso_mediano: mediana(&mut pesos),
+                biomasa,
+                reserva_total,
+                reserva_media: reserva_total.map(|t| t / individuos as f64),
+                cazas,
+                cazas_fallidas,
+                prenadas,
+                crias_perdidas,
+                crias_perdidas_caza,
+                busquedas: busquedas.cantidad,
+                presas_al_alcance: busquedas.presas,
+                depredadores_cerca: busquedas.depredadores,
+                ataques_esperados: busquedas.esperados,
+            }
+        }).collect();
+
+        let fecha = calendario.map(|c| c.fecha(dia));
+        let estacion = calendario.map(|c| c.estacion(dia));
+        self.dias.push(RegistroDia { dia, fecha, estacion, vegetacion, especies });
+        self.nacimientos.clear();
+        self.muertes.clear();
+        self.ataques.clear();
+        self.perdidas.clear();
+        self.busquedas.clear();
+    }
+
+    /// Ataques, cazas y depredadores-día de una especie en toda la corrida.
+    pub fn resumen_caza(&self, especie: &str) -> ResumenCaza {
+        let mut resumen = ResumenCaza::default();
+        for registro in self.dias.iter().flat_map(|d| &d.especies).filter(|e| e.especie == especie) {
+            resumen.intentos += registro.intentos_caza();
+            resumen.cazas += registro.cazas;
+            resumen.depredadores_dia += registro.individuos;
+        }
+        resumen
+    }
+
+    /// Crías en gestación que una especie perdió en toda la corrida:
+    /// (todas, por depredación).
+    pub fn crias_perdidas(&self, especie: &str) -> (usize, usize) {
+        self.dias.iter().flat_map(|d| &d.especies).filter(|e| e.especie == especie)
+            .fold((0, 0), |(todas, caza), e| (todas + e.crias_perdidas, caza + e.crias_perdidas_caza))
+    }
+
+    /// Una fila por día y especie.
+    pub fn a_csv(&self) -> String {
+        let mut csv = String::from(
+            "dia,fecha,estacion,especie,individuos,nacimientos,muertes,peso_medio,peso_mediano,biomasa,reserva_total,reserva_media,intentos_caza,cazas,cazas_fallidas,prenadas,crias_perdidas,crias_perdidas_caza,vegetacion\n",
+        );
+        let opcional = |v: Option<f64>| v.map(|x| format!("{:.4}", x)).unwrap_or_default();
+        for registro in &self.dias {
+            let fecha = registro.fecha.map(|f| f.to_string()).unwrap_or_default();
+            let estacion = registro.estacion.map(|e| e.to_string()).unwrap_or_default();
+            for e in &registro.especies {
+                let _ = writeln!(
+                    csv,
+                    "{},{},{},{},{},{},{},{},{},{:.4},{},{},{},{},{},{},{},{},{:.4}",
+                    registro.dia,
+                    fecha,
+                    estacion,
+                    e.especie,
+                    e.individuos,
+                    e.nacimientos,
+                    e.muertes,
+                    opcional(e.peso_medio),
+                    opcional(e.peso_mediano),
+                    e.biomasa,
+                    opcional(e.reserva_total),
+                    opcional(e.reserva_media),
+                    e.intentos_caza(),
+                    e.cazas,
+                    e.cazas_fallidas,
+                    e.prenadas,
+                    e.crias_perdidas,
+                    e.crias_perdidas_caza,
+                    registro.vegetacion,
+                );
+            }
+        }
+        csv
+    }
+
+    pub fn a_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("el historial siempre se puede serializar")
+    }
+
+    pub fn escribir_csv(&self, ruta: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(ruta, self.a_csv())
+    }
+
+    pub fn escribir_json(&self, ruta: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(ruta, self.a_json())
+    }
+}
+
+// Suma de las salidas a cazar de un depredador en el día.
+#[derive(Debug, Clone, Copy, Default)]
+struct Busquedas {
+    cantidad: usize,
+    presas: usize,
+    depredadores: usize,
+    esperados: f64,
+}
+
+// Lo que se mide de una especie presente al cerrar el día.
+#[derive(Default)]
+struct Foto {
+    pesos: Vec<f64>,
+    prenadas: usize,
+    reservas: Option<Vec<f64>>,
+}
+
+// Mediana de una lista (None si está vacía). La desordena: solo ubica los del medio.
+fn mediana(valores: &mut [f64]) -> Option<f64> {
+    let n = valores.len();
+    if n == 0 {
+        return None;
+    }
+    let (menores, &mut medio, _) = valores.select_nth_unstable_by(n / 2, f64::total_cmp);
+    if n % 2 == 1 {
+        Some(medio)
+    } else {
+        let anterior = menores.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        Some((anterior + medio) / 2.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::herbivoro::{Herbivoro, ParametrosHerbivoro};
+    use crate::organismo::Sexo;
+    use crate::poblacion::TablaHerbivoros;
+    use std::sync::Arc;
+
+    // Un día con tres cabras de peso conocido y un conejo que murió: su tabla quedó vacía.
+    fn un_dia() -> Historial {
+        let mut cabras = TablaHerbivoros::new(Arc::new(ParametrosHerbivoro::cabra()));
+        for (id, peso) in [(1, 30.0), (2, 10.0), (3, 50.0)] {
+            let mut cabra = Herbivoro::new(Arc::clone(&cabras.especie), Sexo::Hembra);
+            cabra.identidad.id = id;
+            cabra.peso = peso;
+            cabras.individuos.push(cabra);
+        }
+        let mut poblacion = Poblacion::new();
+        poblacion.herbivoros = vec![cabras, TablaHerbivoros::new(Arc::new(ParametrosHerbivoro::conejo()))];
+
+        let mut historial = Historial::new();
+        historial.anotar(&Evento::Muerte {
+            dia: 1,
+            id: 4,
+            especie: "Conejo".to_string(),
+            edad: 100,
+            peso: 2.0,
+            causa: CausaMuerte::Vejez,
+        });
+        historial.cerrar_dia(1, &poblacion, 1000.0, None);
+        historial
+    }
+
+    #[test]
+    fn resume_los_pesos_de_cada_especie() {
+        let historial = un_dia();
+        let cabra = &historial.dias[0].especies[0];
+        assert_eq!((cabra.especie.as_str(), cabra.individuos), ("Cabra", 3));
+        assert_eq!(cabra.biomasa, 90.0);
+        assert_eq!(cabra.peso_medio, Some(30.0));
+        assert_eq!(cabra.peso_mediano, Some(30.0));
+        assert_eq!(mediana(&mut [4.0, 1.0, 3.0, 2.0]), Some(2.5));
+    }
+
+    #[test]
+    fn una_especie_sin_individuos_no_tiene_pesos() {
+        let historial = un_dia();
+        let conejo = &historial.dias[0].especies[1];
+        assert_eq!((conejo.especie.as_str(), conejo.individuos, conejo.muertes), ("Conejo", 0, 1));
+        assert!(conejo.biomasa == 0.0 && conejo.biomasa.is_sign_positive());
+        assert_eq!((conejo.peso_medio, conejo.peso_mediano), (None, None));
+
+        assert!(historial.a_csv().contains("\n1,,,Conejo,0,0,1,,,0.0000,"), "{}", historial.a_csv());
+        assert!(historial.a_json().contains("\"peso_medio\": null"));
+    }
+}
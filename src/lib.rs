@@ -0,0 +1,24 @@
+// Módulos compartidos por los dos binarios (consola y macroquad).
+pub mod organismo;
+pub mod simulador;
+pub mod evento;
+pub mod apareamiento;
+pub mod historial;
+pub mod argumentos;
+pub mod escenario;
+pub mod herbivoro;
+pub mod lobo;
+pub mod modelo;
+pub mod vegetacion;
+pub mod espacio;
+pub mod edo;
+pub mod ajuste;
+pub mod montecarlo;
+pub mod barrido;
+pub mod estado;
+pub mod grabacion;
+pub mod poblacion;
+pub mod caza;
+pub mod respuesta;
+pub mod calendario;
+pub mod reproduccion;
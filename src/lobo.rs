@@ -1,34 +1,217 @@
-use crate::organismo::Organismo;
-use crate::modelo::gompertz;
+use crate::calendario::{Forzamiento, Temporada};
+use crate::caza::{Estrategia, ExitoCaza};
+use crate::espacio::{Movimiento, Posicion};
+use crate::modelo::{Crecimiento, CurvaCrecimiento, Gompertz};
+use crate::organismo::{CausaMuerte, Generador, Identidad, Organismo, Sexo};
+use crate::reproduccion::{Ciclo, EstadoReproductivo};
+use crate::respuesta::Respuesta;
 use rand::Rng;
-use std::any::Any;
+use serde::{Deserialize, Serialize};
 
+/// Biología y metabolismo del lobo.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ParametrosLobo {
+    pub crecimiento: Crecimiento,
+    /// Consumo mínimo diario; con menos en la reserva muere de hambre.
+    pub nivel_minimo: f64,
+    /// Consumo diario deseado.
+    pub nivel_optimo: f64,
+    /// Por encima de esta reserva no caza.
+    pub reserva_maxima: f64,
+    /// Por debajo de esta reserva sale a cazar.
+    pub reserva_minima: f64,
+    /// Reserva de los lobos de la población inicial.
+    pub reserva_inicial: f64,
+    /// Comida que la madre cede a cada cachorro.
+    pub reserva_cria: f64,
+    pub edad_madurez: u32,
+    /// Probabilidad diaria de cría de una pareja en condición perfecta (sin `ciclo`).
+    pub probabilidad_cria: f64,
+    pub camada_maxima: u32,
+    /// Edad mínima de las presas que caza.
+    pub edad_minima_presa: u32,
+    /// Cómo elige la presa entre las que tiene a su alcance.
+    #[serde(default)]
+    pub estrategia: Estrategia,
+    /// Probabilidad de que un ataque salga bien; sin ella, todos salen bien.
+    #[serde(default)]
+    pub exito_caza: Option<ExitoCaza>,
+    /// Cuántas presas ataca por día según cuántas tiene cerca. Sin ella,
+    /// ataca una sola vez y solo los días en que necesita cazar.
+    #[serde(default)]
+    pub respuesta: Option<Respuesta>,
+    /// Solo con espacio: cómo se mueve y cuántas celdas por día. Si el
+    /// escenario no los da, los del lobo por defecto (hacia la presa, dos celdas).
+    #[serde(default = "movimiento_por_defecto")]
+    pub movimiento: Movimiento,
+    #[serde(default = "velocidad_por_defecto")]
+    pub velocidad: u32,
+    /// Con calendario: época del año en que cría. Sin ella, todo el año.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temporada_cria: Option<Temporada>,
+    /// Celo, gestación y lactancia de las hembras. Con él, la pareja
+    /// concibe con `ciclo.concepcion` (escalada por su condición) y la
+    /// madre pare al terminar la gestación.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ciclo: Option<Ciclo>,
+}
+
+impl Default for ParametrosLobo {
+    fn default() -> Self {
+        ParametrosLobo {
+            crecimiento: Gompertz { peso_maximo: 50.0, tasa: 0.008, inflexion: 300.0 }.into(),
+            nivel_minimo: 5.0,
+            nivel_optimo: 10.0,
+            reserva_maxima: 200.0,
+            reserva_minima: 50.0,
+            reserva_inicial: 1000.0,
+            reserva_cria: 60.0,
+            edad_madurez: 500,
+            probabilidad_cria: 0.005,
+            camada_maxima: 6,
+            edad_minima_presa: 50,
+            estrategia: Estrategia::MasPesada,
+            exito_caza: None,
+            respuesta: None,
+            movimiento: Movimiento::HaciaComida,
+            velocidad: 2,
+            temporada_cria: None,
+            ciclo: None,
+        }
+    }
+}
+
+fn movimiento_por_defecto() -> Movimiento {
+    ParametrosLobo::default().movimiento
+}
+
+fn velocidad_por_defecto() -> u32 {
+    ParametrosLobo::default().velocidad
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Lobo {
+    pub identidad: Identidad,
     pub edad: u32,
-    pub peso: f64, // peso corporal (crece con gompertz)
+    pub peso: f64, // peso corporal (sigue la curva de crecimiento)
+    pub sexo: Sexo,
     pub reserva: f64, // comida acumulada
     pub vivo: bool,
     pub nivel_minimo: f64,  //Consumo min
     pub nivel_optimo: f64,  //Consumo diario deseadp
     pub reserva_maxima: f64,  
     pub reserva_minima: f64,
+    pub reserva_cria: f64,  // comida que la madre le pasa a cada cachorro
+    pub crecimiento: Crecimiento,
+    pub edad_madurez: u32,
+    pub probabilidad_cria: f64,
+    pub camada_maxima: u32,
+    pub edad_minima_presa: u32,  // no caza presas más jóvenes
+    #[serde(default)]
+    pub estrategia: Estrategia, // cómo elige la presa; los cachorros heredan la de la madre
+    #[serde(default)]
+    pub exito_caza: Option<ExitoCaza>, // None: todos los ataques salen bien
+    #[serde(default)]
+    pub respuesta: Option<Respuesta>, // None: un ataque por día, solo con hambre
+    pub ultimo_consumo: f64, // lo que comió de su reserva el último día
+    pub posicion: Posicion,
+    pub movimiento: Movimiento,
+    pub velocidad: u32,
+    #[serde(default)]
+    pub temporada_cria: Option<Temporada>, // None: cría en cualquier época
+    #[serde(default)]
+    pub ciclo: Option<Ciclo>, // None: pare el mismo día en que se aparea
+    #[serde(default)]
+    pub reproduccion: EstadoReproductivo,
+    pub causa_muerte: Option<CausaMuerte>,
 }
 
 impl Lobo {
-    pub fn new() -> Self {
+    // Crea un lobo de población inicial con sexo aleatorio y los parámetros por defecto.
+    pub fn new_random(rng: &mut Generador) -> Self {
+        Lobo::new_con_sexo(Sexo::aleatorio(rng))
+    }
+
+    pub fn new_con_sexo(sexo: Sexo) -> Self {
+        Lobo::con_parametros(&ParametrosLobo::default(), sexo)
+    }
+
+    // Crea un lobo de población inicial con la biología de un escenario.
+    pub fn con_parametros(p: &ParametrosLobo, sexo: Sexo) -> Self {
         Lobo {
+            identidad: Identidad::default(),
             edad: 0,
             peso: 0.0,
-            reserva: 1000.0,  //Empezamos con reserva alta, para permitir reproduccion
+            sexo,
+            reserva: p.reserva_inicial,  //Empezamos con reserva alta, para permitir reproduccion
             vivo: true,
-            nivel_minimo: 5.0,   
-            nivel_optimo: 10.0,
-            reserva_maxima: 200.0,
-            reserva_minima: 50.0,  
+            nivel_minimo: p.nivel_minimo,
+            nivel_optimo: p.nivel_optimo,
+            reserva_maxima: p.reserva_maxima,
+            reserva_minima: p.reserva_minima,
+            reserva_cria: p.reserva_cria,
+            crecimiento: p.crecimiento,
+            edad_madurez: p.edad_madurez,
+            probabilidad_cria: p.probabilidad_cria,
+            camada_maxima: p.camada_maxima,
+            edad_minima_presa: p.edad_minima_presa,
+            estrategia: p.estrategia,
+            exito_caza: p.exito_caza,
+            respuesta: p.respuesta,
+            ultimo_consumo: 0.0,
+            posicion: Posicion::default(),
+            movimiento: p.movimiento,
+            velocidad: p.velocidad,
+            temporada_cria: p.temporada_cria,
+            ciclo: p.ciclo,
+            reproduccion: EstadoReproductivo::default(),
+            causa_muerte: None,
+        }
+    }
+
+    /// La biología del lobo como parámetros de escenario; la reserva
+    /// inicial es la que tiene ahora.
+    pub fn parametros(&self) -> ParametrosLobo {
+        ParametrosLobo {
+            crecimiento: self.crecimiento,
+            nivel_minimo: self.nivel_minimo,
+            nivel_optimo: self.nivel_optimo,
+            reserva_maxima: self.reserva_maxima,
+            reserva_minima: self.reserva_minima,
+            reserva_inicial: self.reserva,
+            reserva_cria: self.reserva_cria,
+            edad_madurez: self.edad_madurez,
+            probabilidad_cria: self.probabilidad_cria,
+            camada_maxima: self.camada_maxima,
+            edad_minima_presa: self.edad_minima_presa,
+            estrategia: self.estrategia,
+            exito_caza: self.exito_caza,
+            respuesta: self.respuesta,
+            movimiento: self.movimiento,
+            velocidad: self.velocidad,
+            temporada_cria: self.temporada_cria,
+            ciclo: self.ciclo,
         }
     }
 
-        pub fn necesita_cazar(&self) -> bool {
+    // Un cachorro: hereda la biología de la madre.
+    fn cachorro(&self, sexo: Sexo) -> Self {
+        Lobo {
+            identidad: Identidad::default(),
+            edad: 0,
+            peso: 0.0,
+            sexo,
+            reserva: self.reserva_cria,
+            vivo: true,
+            ultimo_consumo: 0.0,
+            reproduccion: EstadoReproductivo::default(),
+            causa_muerte: None,
+            ..*self
+        }
+    }
+
+    pub fn necesita_cazar(&self) -> bool {
         self.reserva < self.reserva_minima
     }
 
@@ -36,66 +219,238 @@ impl Lobo {
         self.reserva >= self.reserva_maxima
     }
 
+    /// Si hoy sale a cazar: con respuesta funcional, todos los días (la
+    /// saciedad la pone el tiempo de manipulación); sin ella, solo con hambre.
+    pub fn sale_a_cazar(&self) -> bool {
+        self.respuesta.is_some() || (self.necesita_cazar() && !self.tiene_reserva_llena())
+    }
+
 
-    /// El lobo consume de su reserva
-    fn alimentarse(&mut self) {
+    /// El lobo consume de su reserva; `factor` escala sus necesidades (el frío pide más).
+    fn alimentarse(&mut self, factor: f64, rng: &mut Generador) {
         if !self.vivo { return; }
+        let (minimo, optimo) = (self.nivel_minimo * factor, self.nivel_optimo * factor);
 
-        if self.reserva < self.nivel_minimo {
-            println!("☠️ El lobo murió de hambre con {:.2} kg en reserva.", self.reserva);
+        if self.reserva < minimo {
             self.vivo = false;
+            self.causa_muerte = Some(CausaMuerte::Hambre);
+            self.ultimo_consumo = 0.0;
         } else {
             // consumo aleatorio entre mínimo y óptimo
-            let mut rng = rand::thread_rng();
-            let consumo_deseado = rng.gen_range(self.nivel_minimo..=self.nivel_optimo);
+            let consumo_deseado = rng.random_range(minimo..=optimo);
             let consumo = consumo_deseado.min(self.reserva);
 
             self.reserva -= consumo;
-            println!(
-                " El lobo consumió {:.2} kg de su reserva (quedan {:.2})",
-                consumo, self.reserva
-            );
+            self.ultimo_consumo = consumo;
         }
     }
 
 
+    /// Si puede aparearse hoy: maduro y, si es hembra, en celo.
+    pub fn en_celo(&self) -> bool {
+        self.es_maduro() && self.reproduccion.en_celo()
+    }
+
+    /// Cría de una pareja: la hembra (`self`) pare con el macho `padre`.
+    /// La probabilidad y el tamaño de la camada dependen de las reservas de
+    /// ambos, y la comida inicial de los cachorros sale de la reserva de la madre.
+    /// Con ciclo reproductivo la hembra solo queda preñada: la camada se
+    /// decide hoy y nace en `gestar`.
+    pub fn criar(&mut self, padre: &Lobo, rng: &mut Generador) -> Vec<Lobo> {
+        if self.sexo != Sexo::Hembra || padre.sexo != Sexo::Macho
+            || !self.en_celo() || !padre.es_maduro() {
+            return Vec::new();
+        }
+
+        // Condición de la pareja: 0 con reservas en el mínimo, 1 con la reserva máxima.
+        let condicion = |l: &Lobo| {
+            ((l.reserva - l.reserva_minima) / (l.reserva_maxima - l.reserva_minima)).clamp(0.0, 1.0)
+        };
+        let condicion_pareja = condicion(self) * condicion(padre);
+        let probabilidad = self.ciclo.map_or(self.probabilidad_cria, |c| c.concepcion);
+        if condicion_pareja <= 0.0 || !rng.random_bool(probabilidad * condicion_pareja) {
+            return Vec::new();
+        }
+
+        // Camada más grande si la pareja está bien alimentada,
+        // y limitada por la comida que la madre puede ceder sin bajar de su mínimo.
+        let deseada = (rng.random_range(1..=self.camada_maxima) as f64 * condicion_pareja).ceil() as u32;
+        let cantidad = deseada.min(self.cachorros_que_alcanza());
+
+        match self.ciclo {
+            Some(ciclo) if cantidad > 0 => {
+                self.reproduccion.concebir(&ciclo, padre.identidad.id, cantidad);
+                Vec::new()
+            }
+            Some(_) => Vec::new(),
+            None => self.parir(cantidad, padre.identidad.id, rng),
+        }
+    }
+
+    /// Pasa un día del ciclo reproductivo; si termina la gestación, pare.
+    /// Si desde la concepción adelgazó, solo nacen los cachorros que puede
+    /// alimentar sin bajar de su mínimo: devuelve los nacidos y cuántos se perdieron.
+    pub fn gestar(&mut self, rng: &mut Generador) -> (Vec<Lobo>, u32) {
+        let Some(ciclo) = self.ciclo else { return (Vec::new(), 0) };
+        match self.reproduccion.avanzar(&ciclo) {
+            Some(parto) => {
+                let nacen = parto.camada.min(self.cachorros_que_alcanza());
+                (self.parir(nacen, parto.padre, rng), parto.camada - nacen)
+            }
+            None => (Vec::new(), 0),
+        }
+    }
+
+    // Cuántos cachorros puede ceder `reserva_cria` sin bajar de su reserva mínima.
+    fn cachorros_que_alcanza(&self) -> u32 {
+        ((self.reserva - self.reserva_minima) / self.reserva_cria).floor().max(0.0) as u32
+    }
+
+    // Cada cachorro se lleva `reserva_cria` de la reserva de la madre.
+    fn parir(&mut self, cantidad: u32, padre: u64, rng: &mut Generador) -> Vec<Lobo> {
+        let mut crias = Vec::with_capacity(cantidad as usize);
+        for _ in 0..cantidad {
+            let mut cria = self.cachorro(Sexo::aleatorio(rng));
+            cria.identidad = Identidad::cria_de(self.identidad.id, Some(padre));
+            self.reserva -= self.reserva_cria;
+            crias.push(cria);
+        }
+        crias
+    }
+
     /// Permite aumentar la reserva. cuando caza el lobo
     pub fn agregar_comida(&mut self, cantidad: f64) {
         if !self.vivo { return; }
         self.reserva += cantidad;
-        println!(" El lobo almacenó {:.2} kg de comida (total reserva: {:.2})", cantidad, self.reserva);
+    }
+
+    /// Lo que gasta de su reserva en un ataque fallido (sin bajar de 0).
+    pub fn gastar(&mut self, cantidad: f64) -> f64 {
+        let gasto = cantidad.min(self.reserva).max(0.0);
+        self.reserva -= gasto;
+        gasto
     }
 }
 
 //Implementacion del trait
 impl Organismo for Lobo {
-    fn envejecer(&mut self) {
+    fn envejecer(&mut self, forzamiento: &Forzamiento, rng: &mut Generador) {
         if !self.vivo { return; }
         self.edad += 1;
-        self.peso = gompertz(self.edad as f64, 50.0, 0.008, 300.0);
-        self.alimentarse(); // cada día consume de la reserva
-    }
-
-    //POr ahora no se reproduce 
-    fn reproducirse(&self, rng: &mut rand::rngs::ThreadRng) -> Vec<Box<dyn Organismo>> {
-        if self.edad > 500 && rng.gen_bool(0.005) {
-            vec![Box::new(Lobo::new())]
-        } else {
-            vec![]
-        }
+        self.peso = self.crecimiento.peso(self.edad as f64);
+        self.alimentarse(forzamiento.consumo_lobo, rng); // cada día consume de la reserva
     }
 
     fn peso(&self) -> f64 { self.peso }
     fn esta_vivo(&self) -> bool { self.vivo }
     fn nombre(&self) -> &str { "Lobo" }
     fn edad(&self) -> u32 { self.edad }
+    fn sexo(&self) -> Sexo { self.sexo }
+    fn es_maduro(&self) -> bool { self.vivo && self.edad > self.edad_madurez }
+    fn causa_muerte(&self) -> Option<CausaMuerte> { self.causa_muerte }
+    fn gestando(&self) -> u32 { self.reproduccion.gestando() }
+    fn identidad(&self) -> &Identidad { &self.identidad }
+    fn identidad_mut(&mut self) -> &mut Identidad { &mut self.identidad }
+    fn posicion(&self) -> Posicion { self.posicion }
+    fn posicion_mut(&mut self) -> &mut Posicion { &mut self.posicion }
+    fn movimiento(&self) -> Movimiento { self.movimiento }
+    fn velocidad(&self) -> u32 { self.velocidad }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    // Una pareja adulta de lobos, los dos con la misma reserva.
+    fn pareja(p: &ParametrosLobo, reserva: f64) -> (Lobo, Lobo) {
+        let adulto = |sexo, id| {
+            let mut lobo = Lobo::con_parametros(p, sexo);
+            lobo.identidad.id = id;
+            lobo.edad = p.edad_madurez + 1;
+            lobo.reserva = reserva;
+            lobo
+        };
+        (adulto(Sexo::Hembra, 1), adulto(Sexo::Macho, 2))
+    }
 
-        fn as_any(&self) -> &dyn Any {
-        self
+    #[test]
+    fn solo_cria_una_hembra_madura_con_un_macho_maduro() {
+        let p = ParametrosLobo { probabilidad_cria: 1.0, reserva_maxima: 500.0, ..Default::default() };
+        let mut rng = Generador::seed_from_u64(1);
+        let (mut hembra, mut macho) = pareja(&p, 500.0);
+        let (otra, _) = pareja(&p, 500.0);
+        assert!(macho.criar(&hembra, &mut rng).is_empty());
+        assert!(hembra.criar(&otra, &mut rng).is_empty());
+        let mut joven = macho.clone();
+        joven.edad = p.edad_madurez;
+        assert!(hembra.criar(&joven, &mut rng).is_empty());
+        // Sin reserva por encima del mínimo no hay camada.
+        let (mut flaca, flaco) = pareja(&p, p.reserva_minima);
+        assert!(flaca.criar(&flaco, &mut rng).is_empty());
+        assert!(!hembra.criar(&macho, &mut rng).is_empty());
     }
 
-    fn as_any_mut(&mut self) -> &mut dyn Any {
-        self
+    #[test]
+    fn los_cachorros_salen_de_la_reserva_de_la_madre() {
+        let p = ParametrosLobo { probabilidad_cria: 1.0, reserva_maxima: 500.0, ..Default::default() };
+        let mut tamanos = Vec::new();
+        for semilla in 0..50 {
+            let mut rng = Generador::seed_from_u64(semilla);
+            let (mut madre, padre) = pareja(&p, 500.0);
+            let cachorros = madre.criar(&padre, &mut rng);
+
+            // Lo que tienen los cachorros es lo que perdió la madre; el padre no pone nada.
+            let cedido: f64 = cachorros.iter().map(|c| c.reserva).sum();
+            assert_eq!(madre.reserva + cedido, 500.0);
+            assert!(cachorros.iter().all(|c| c.reserva == p.reserva_cria && c.edad == 0));
+            assert!(cachorros.iter().all(|c| c.identidad.madre == Some(1) && c.identidad.padre == Some(2)));
+            assert_eq!(padre.reserva, 500.0);
+            tamanos.push(cachorros.len() as u32);
+        }
+        assert!(tamanos.iter().all(|&n| (1..=p.camada_maxima).contains(&n)));
+        assert!(tamanos.iter().any(|&n| n > 1));
+
+        // Con reserva para un solo cachorro sin bajar de su mínimo, nace uno.
+        let p = ParametrosLobo { reserva_maxima: 140.0, ..p };
+        for semilla in 0..20 {
+            let (mut madre, padre) = pareja(&p, 140.0);
+            let cachorros = madre.criar(&padre, &mut Generador::seed_from_u64(semilla));
+            assert_eq!(cachorros.len(), 1);
+            assert!(madre.reserva >= p.reserva_minima);
+        }
     }
 
+    #[test]
+    fn una_pareja_peor_alimentada_cria_menos() {
+        let p = ParametrosLobo { probabilidad_cria: 0.5, reserva_maxima: 500.0, ..Default::default() };
+        let cachorros = |reserva: f64| -> usize {
+            let mut rng = Generador::seed_from_u64(8);
+            (0..400).map(|_| {
+                let (mut madre, padre) = pareja(&p, reserva);
+                madre.criar(&padre, &mut rng).len()
+            }).sum()
+        };
+        assert!(cachorros(200.0) * 3 < cachorros(500.0));
+    }
+
+    #[test]
+    fn si_adelgaza_en_la_gestacion_pierde_los_cachorros_que_no_puede_alimentar() {
+        let ciclo = Ciclo { concepcion: 1.0, gestacion: 3, intervalo_partos: 10 };
+        let p = ParametrosLobo { reserva_maxima: 500.0, camada_maxima: 4, ciclo: Some(ciclo), ..Default::default() };
+        let mut rng = Generador::seed_from_u64(4);
+        let (mut madre, padre) = pareja(&p, 500.0);
+        assert!(madre.criar(&padre, &mut rng).is_empty());
+        let camada = madre.gestando();
+        assert!(camada > 1);
+
+        // Al parir le alcanza para un solo cachorro.
+        madre.reserva = p.reserva_minima + p.reserva_cria;
+        assert_eq!(madre.gestar(&mut rng), (Vec::new(), 0));
+        assert_eq!(madre.gestar(&mut rng), (Vec::new(), 0));
+        let (nacidos, perdidos) = madre.gestar(&mut rng);
+        assert_eq!((nacidos.len() as u32, perdidos), (1, camada - 1));
+        assert_eq!(madre.reserva, p.reserva_minima);
+        assert_eq!(madre.gestando(), 0);
+    }
 }
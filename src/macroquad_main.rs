@@ -1,161 +1,384 @@
-mod organismo;
-mod simulador;
-mod cabra;
-mod conejo;
-mod lobo;
-mod modelo;
-
 use macroquad::prelude::*;
-use ::rand::Rng;      
-use ::rand::rngs::ThreadRng;
-
-use simulador::Simulador;
-use cabra::Cabra;
-use conejo::Conejo;
-use lobo::Lobo;
-
-// Función para poblar la simulación con organismos iniciales
-// Función para poblar la simulación 
-fn poblar(sim: &mut Simulador, rng: &mut ThreadRng) {
-    for _ in 0..150{
-        sim.agregar(Box::new(Cabra::new_random(rng)));
-    }
-    for _ in 0..40 {
-        sim.agregar(Box::new(Conejo::new_random(rng)));
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use juego_mejorado::argumentos::{Argumentos, ErrorArgumentos, PoblacionInicial, AYUDA};
+use juego_mejorado::calendario::Calendario;
+use juego_mejorado::escenario::Escenario;
+use juego_mejorado::estado::Estado;
+use juego_mejorado::evento::Evento;
+use juego_mejorado::grabacion::{Fotograma, Grabacion};
+use juego_mejorado::simulador::Simulador;
+
+// Colores de las barras, en el orden en que el escenario define las especies.
+const COLORES: [Color; 8] = [RED, GREEN, BLUE, ORANGE, PURPLE, BROWN, MAGENTA, DARKGREEN];
+
+// Segundos por día a velocidad normal.
+const INTERVALO_DIA: f32 = 0.6;
+
+// Cuántas cazas recientes se muestran.
+const CAZAS_VISIBLES: usize = 30;
+
+// Últimos eventos de caza que se muestran en pantalla.
+type Cazas = Rc<RefCell<VecDeque<String>>>;
+
+// Lo que se dibuja en un cuadro, venga de la simulación en vivo o de una grabación.
+struct Cuadro<'a> {
+    titulo: String,
+    fotograma: &'a Fotograma,
+    cazas: &'a [String],
+    ayuda: &'a str,
+    aviso: &'a str,
+    // Solo al reproducir: qué fracción de la grabación ya se vio.
+    progreso: Option<f32>,
+}
+
+// Crea el simulador (desde el estado guardado, si lo hay, o desde el escenario)
+// y lo conecta a la lista de cazas de la interfaz.
+fn crear_simulador(escenario: &Escenario, guardado: Option<&Estado>, cazas: &Cazas) -> Simulador {
+    let mut sim = match guardado.cloned().map(Estado::restaurar) {
+        Some(Ok(sim)) => sim,
+        Some(Err(e)) => {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        }
+        None => escenario.crear_simulador(None),
+    };
+    let cazas = Rc::clone(cazas);
+    sim.suscribir(move |evento: &Evento| {
+        if let Evento::Caza { .. } | Evento::Escape { .. } = evento {
+            let mut cazas = cazas.borrow_mut();
+            // Se guardan las más recientes.
+            if cazas.len() == CAZAS_VISIBLES {
+                cazas.pop_front();
+            }
+            cazas.push_back(evento.to_string());
+        }
+    });
+    sim
+}
+
+// " (2024-03-21, primavera)" para el título, si la corrida tiene calendario
+// y ya empezó.
+fn fecha_de(calendario: Option<&Calendario>, dia: u32) -> String {
+    match calendario {
+        Some(c) if dia > 0 => format!(" ({})", c.describir(dia)),
+        _ => String::new(),
     }
-    sim.agregar(Box::new(Lobo::new()));
 }
 
-#[macroquad::main("Ecosistema - Barras")]
-async fn main() {
-    // Configuración inicial
-    let mut sim = Simulador::new();
-    let mut rng = ::rand::thread_rng();
+// Barra de avance de la reproducción (también es donde se hace clic para saltar).
+fn barra_progreso() -> Rect {
+    Rect::new(24.0, screen_height() - 48.0, screen_width() - 48.0, 8.0)
+}
 
-    poblar(&mut sim, &mut rng);
+// Dibuja las barras por especie, el pasto, las reservas de los lobos y las cazas.
+// `display` guarda las alturas animadas entre cuadros.
+fn dibujar(cuadro: &Cuadro, especies: &[String], display: &mut [f32], dt: f32) {
+    let Cuadro { fotograma, .. } = cuadro;
 
-    // Estados de la UI / animación
-    let mut dia: u32 = 0;
-    let mut timer = 0.0_f32;
-    let intervalo_dia = 0.6_f32; // segundos por día
+    // contar especies
+    let cantidades: Vec<f32> = especies.iter()
+        .map(|e| fotograma.censo.get(e).copied().unwrap_or(0) as f32)
+        .collect();
 
-    let mut paused = false;
+    // maximo para escalar
+    let max_poblacion = cantidades.iter().copied().fold(1.0, f32::max);
 
-    // valores animados de altura
-    let mut display_cabras: f32 = 0.0;
-    let mut display_conejos: f32 = 0.0;
-    let mut display_lobos: f32 = 0.0;
+    // dimensiones
+    let screen_w = screen_width();
+    let screen_h = screen_height();
+    let base_y = screen_h - 90.0;
+    let max_bar_h = screen_h - 220.0;
+    let scale = max_bar_h / max_poblacion;
+
+    let n = especies.len() as f32;
+    let gap = 40.0;
+    let bar_w = ((screen_w - 80.0 - (n - 1.0) * gap) / n).min(140.0);
+    let total_w = n * bar_w + (n - 1.0) * gap;
+    let start_x = (screen_w - total_w) / 2.0;
+
+    // animación suave
+    let anim_speed = 6.0;
+    for (actual, cantidad) in display.iter_mut().zip(&cantidades) {
+        *actual += (cantidad * scale - *actual) * (1.0 - (-anim_speed * dt).exp());
+    }
+
+    // Dibujo
+    clear_background(WHITE);
+
+    draw_text(&cuadro.titulo, 24.0, 36.0, 30.0, BLACK);
+    draw_text(
+        &format!("Población por especie (barras)    Pasto: {:.0} kg", fotograma.vegetacion),
+        24.0,
+        64.0,
+        20.0,
+        DARKGRAY,
+    );
+
+    draw_line(start_x - 20.0, base_y, start_x + total_w + 20.0, base_y, 2.0, BLACK);
+
+    for (i, especie) in especies.iter().enumerate() {
+        let x = start_x + i as f32 * (bar_w + gap);
+        draw_rectangle(x, base_y - display[i], bar_w, display[i], COLORES[i % COLORES.len()]);
+        draw_text(&format!("{}\n{}", especie, cantidades[i] as i32), x + 12.0, base_y + 30.0, 22.0, BLACK);
+    }
+
+    // info
+    let total: usize = fotograma.censo.values().sum();
+    draw_text(&format!("Total población: {}", total), 24.0, screen_h - 20.0, 20.0, DARKBLUE);
+    let ancho_ayuda = measure_text(cuadro.ayuda, None, 18, 1.0).width;
+    draw_text(cuadro.ayuda, screen_w - ancho_ayuda - 24.0, screen_h - 20.0, 18.0, DARKGRAY);
+    if !cuadro.aviso.is_empty() {
+        draw_text(cuadro.aviso, 24.0, screen_h - 44.0, 18.0, DARKGREEN);
+    }
+    if let Some(progreso) = cuadro.progreso {
+        let barra = barra_progreso();
+        draw_rectangle(barra.x, barra.y, barra.w, barra.h, LIGHTGRAY);
+        draw_rectangle(barra.x, barra.y, barra.w * progreso, barra.h, DARKBLUE);
+    }
+
+    // Mostrar el alimento de cada lobo
+    let reservas: Vec<String> = fotograma.reservas.iter().map(|r| format!("{:.1}", r)).collect();
+    if !reservas.is_empty() {
+        draw_text(
+            &format!("🐺 Alimento de los lobos: {} kg", reservas.join(" / ")),
+            24.0,
+            100.0,
+            24.0,
+            DARKBLUE,
+        );
+    }
+
+    let mut y_eventos = 150.0;
+
+    // Dibujar eventos de caza del lobo
+    for evento in cuadro.cazas {
+        draw_text(evento, 24.0, y_eventos, 20.0, DARKBLUE);
+        y_eventos += 24.0; // espacio entre líneas
+    }
+}
+
+// Reproduce una corrida grabada por `simulador --grabar`, sin volver a simular.
+async fn reproducir(grabacion: Grabacion) {
+    let ultimo = grabacion.fotogramas.len() - 1;
+    let dia_final = grabacion.fotogramas[ultimo].dia;
+    let mut actual = 0;
+    let mut reloj = 0.0_f32;
+    let mut velocidad = 1.0_f32;
+    let mut pausado = false;
+    let mut display = vec![0.0_f32; grabacion.especies.len()];
 
     loop {
         let dt = get_frame_time();
 
         // teclas
         if is_key_pressed(KeyCode::Space) {
-            paused = !paused;
+            // Terminada la grabación, Space la empieza de nuevo.
+            if actual == ultimo {
+                actual = 0;
+                pausado = false;
+            } else {
+                pausado = !pausado;
+            }
         }
-        if is_key_pressed(KeyCode::R) {
-            sim = Simulador::new();
-            poblar(&mut sim, &mut rng);
-            dia = 0;
-            display_cabras = 0.0;
-            display_conejos = 0.0;
-            display_lobos = 0.0;
-            timer = 0.0;
+        if is_key_pressed(KeyCode::Right) {
+            actual = (actual + 1).min(ultimo);
+        }
+        if is_key_pressed(KeyCode::Left) {
+            actual = actual.saturating_sub(1);
+        }
+        if is_key_pressed(KeyCode::Home) {
+            actual = 0;
+        }
+        if is_key_pressed(KeyCode::End) {
+            actual = ultimo;
+        }
+        if is_key_pressed(KeyCode::Up) {
+            velocidad = (velocidad * 2.0).min(64.0);
+        }
+        if is_key_pressed(KeyCode::Down) {
+            velocidad = (velocidad / 2.0).max(0.25);
         }
 
-        // avanzar simulación
-        if !paused {
-            timer += dt;
-            if timer >= intervalo_dia {
-                timer -= intervalo_dia;
-                dia = dia.saturating_add(1);
-                sim.simular_dia(dia);
-            }
+        // Clic (o arrastre) sobre la barra: salta a ese punto.
+        let barra = barra_progreso();
+        let (x, y) = mouse_position();
+        if is_mouse_button_down(MouseButton::Left) && x >= barra.x && x <= barra.right()
+            && (y - barra.center().y).abs() <= 12.0 {
+            actual = (((x - barra.x) / barra.w) * ultimo as f32).round() as usize;
+            reloj = 0.0;
         }
 
-        // contar especies
-        let num_cabras: f32 = sim.contar::<Cabra>() as f32;
-        let num_conejos: f32 = sim.contar::<Conejo>() as f32;
-        let num_lobos: f32 = sim.contar::<Lobo>() as f32;
+        // avanzar reproducción
+        if !pausado && actual < ultimo {
+            reloj += dt * velocidad;
+            while reloj >= INTERVALO_DIA && actual < ultimo {
+                reloj -= INTERVALO_DIA;
+                actual += 1;
+            }
+        }
 
-        // maximo para escalar
-        let max_poblacion = num_cabras.max(num_conejos).max(num_lobos).max(1.0);
+        // Las cazas más recientes hasta el día que se muestra.
+        let mut cazas: Vec<String> = grabacion.fotogramas[..=actual].iter().rev()
+            .flat_map(|f| f.cazas.iter().rev())
+            .take(CAZAS_VISIBLES)
+            .map(|e| e.to_string())
+            .collect();
+        cazas.reverse();
 
-        // dimensiones
-        let screen_w = screen_width();
-        let screen_h = screen_height();
-        let base_y = screen_h - 90.0;
-        let max_bar_h = screen_h - 220.0;
-        let scale = max_bar_h / max_poblacion;
+        let fotograma = &grabacion.fotogramas[actual];
+        let marca = if actual == ultimo { " (FIN)" } else if pausado { " (PAUSADO)" } else { "" };
+        let fecha = fecha_de(grabacion.calendario.as_ref(), fotograma.dia);
+        let cuadro = Cuadro {
+            titulo: format!(
+                "Reproducción - Día: {}{} de {}  (semilla {})  x{}{}",
+                fotograma.dia, fecha, dia_final, grabacion.semilla, velocidad, marca
+            ),
+            fotograma,
+            cazas: &cazas,
+            ayuda: "Space: Pausa    ←/→: Día    Inicio/Fin    ↑/↓: Velocidad",
+            aviso: "",
+            progreso: Some(if ultimo == 0 { 1.0 } else { actual as f32 / ultimo as f32 }),
+        };
+        dibujar(&cuadro, &grabacion.especies, &mut display, dt);
 
-        let bar_w = 140.0;
-        let gap = 40.0;
-        let total_w = 3.0 * bar_w + 2.0 * gap;
-        let start_x = (screen_w - total_w) / 2.0;
+        next_frame().await;
+    }
+}
 
-        let x_cabras = start_x;
-        let x_conejos = start_x + (bar_w + gap);
-        let x_lobos = start_x + 2.0 * (bar_w + gap);
+#[macroquad::main("Ecosistema - Barras")]
+async fn main() {
+    // Mismas opciones que el simulador de consola (semilla, escenario, población).
+    let args = match Argumentos::desde(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(ErrorArgumentos::Ayuda) => {
+            print!("{}", AYUDA);
+            return;
+        }
+        Err(e) => {
+            eprintln!("error: {}\n\nUse --ayuda para ver las opciones.", e);
+            std::process::exit(2);
+        }
+    };
 
-        // animación suave
-        let anim_speed = 6.0;
-        let target_cabras = num_cabras * scale;
-        let target_conejos = num_conejos * scale;
-        let target_lobos = num_lobos * scale;
+    if let Some(ruta) = &args.reproducir {
+        match Grabacion::cargar(ruta) {
+            Ok(grabacion) => reproducir(grabacion).await,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
 
-        display_cabras += (target_cabras - display_cabras) * (1.0 - (-anim_speed * dt).exp());
-        display_conejos += (target_conejos - display_conejos) * (1.0 - (-anim_speed * dt).exp());
-        display_lobos += (target_lobos - display_lobos) * (1.0 - (-anim_speed * dt).exp());
+    // Sin escenario, la interfaz arranca con más presas que la consola.
+    let mut escenario = Escenario::default();
+    escenario.poblacion.insert("Cabra".to_string(), PoblacionInicial { aleatorios: 150, ..Default::default() });
+    escenario.poblacion.insert("Conejo".to_string(), PoblacionInicial { aleatorios: 40, ..Default::default() });
+    if let Some(ruta) = &args.escenario {
+        escenario = match Escenario::cargar(ruta) {
+            Ok(e) => e,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            }
+        };
+    }
+    args.aplicar(&mut escenario);
+    if let Err(e) = escenario.validar() {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    }
 
-        // Dibujo
-        clear_background(WHITE);
+    // Con --cargar se sigue una corrida guardada; R vuelve a ese punto.
+    let guardado = args.cargar.as_ref().map(|ruta| match Estado::cargar(ruta) {
+        Ok(estado) => estado,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        }
+    });
+    // G guarda el estado en la ruta de --guardar (o en ecosistema.estado).
+    let ruta_guardado = args.guardar.clone().unwrap_or_else(|| "ecosistema.estado".into());
+    let mut aviso = String::new();
 
-        draw_text(
-            &format!("Ecosistema - Día: {}{}", dia, if paused { " (PAUSADO)" } else { "" }),
-            24.0,
-            36.0,
-            30.0,
-            BLACK,
-        );
-        draw_text("Población por especie (barras)", 24.0, 64.0, 20.0, DARKGRAY);
+    // Una barra por especie: los herbívoros (del escenario o del estado) y el lobo.
+    let herbivoros = match &guardado {
+        Some(estado) => &estado.especies,
+        None => &escenario.herbivoros,
+    };
+    let especies: Vec<String> = herbivoros.iter()
+        .map(|h| h.nombre.clone())
+        .chain(std::iter::once("Lobo".to_string()))
+        .collect();
 
-        draw_line(start_x - 20.0, base_y, start_x + total_w + 20.0, base_y, 2.0, BLACK);
+    // Configuración inicial
+    let cazas: Cazas = Rc::new(RefCell::new(VecDeque::with_capacity(CAZAS_VISIBLES)));
+    let mut sim = crear_simulador(&escenario, guardado.as_ref(), &cazas);
 
-        // Cabras
-        draw_rectangle(x_cabras, base_y - display_cabras, bar_w, display_cabras, RED);
-        draw_text(&format!("Cabras\n{}", num_cabras as i32), x_cabras + 12.0, base_y + 30.0, 22.0, BLACK);
+    // Estados de la UI / animación
+    let mut dia: u32 = sim.dia();
+    let mut timer = 0.0_f32;
 
-        // Conejos
-        draw_rectangle(x_conejos, base_y - display_conejos, bar_w, display_conejos, GREEN);
-        draw_text(&format!("Conejos\n{}", num_conejos as i32), x_conejos + 12.0, base_y + 30.0, 22.0, BLACK);
+    let mut paused = false;
 
-        // Lobos
-        draw_rectangle(x_lobos, base_y - display_lobos, bar_w, display_lobos, BLUE);
-        draw_text(&format!("Lobos\n{}", num_lobos as i32), x_lobos + 12.0, base_y + 30.0, 22.0, BLACK);
+    // valores animados de altura
+    let mut display = vec![0.0_f32; especies.len()];
 
-        // info
-        draw_text(&format!("Total población: {}", sim.poblacion.len()), 24.0, screen_h - 20.0, 20.0, DARKBLUE);
-        draw_text("Space: Pausa/Reanuda    R: Reiniciar", screen_w - 420.0, screen_h - 20.0, 18.0, DARKGRAY);
+    loop {
+        let dt = get_frame_time();
 
-        // Buscar lobo y mostrar alimento
-        if let Some(lobo) = sim.poblacion.iter().find_map(|o| o.as_any().downcast_ref::<Lobo>()) {
-            draw_text(
-                &format!("🐺 Alimento del lobo: {:.1} kg", lobo.reserva),
-                24.0,
-                100.0,
-                24.0,
-                DARKBLUE,
-            );
+        // teclas
+        if is_key_pressed(KeyCode::Space) {
+            paused = !paused;
+        }
+        if is_key_pressed(KeyCode::R) {
+            // Con semilla fija se repite la misma corrida; sin ella, una nueva.
+            cazas.borrow_mut().clear();
+            sim = crear_simulador(&escenario, guardado.as_ref(), &cazas);
+            dia = sim.dia();
+            display.fill(0.0);
+            timer = 0.0;
         }
 
-        let mut y_eventos = 150.0;
+        if is_key_pressed(KeyCode::G) {
+            aviso = match Estado::de(&sim).guardar(&ruta_guardado) {
+                Ok(()) => format!("Guardado el día {} en {}", dia, ruta_guardado.display()),
+                Err(e) => format!("No se pudo guardar: {}", e),
+            };
+        }
 
-        // Dibujar eventos de caza del lobo
-        for evento in &sim.eventos {
-            draw_text(evento, 24.0, y_eventos, 20.0, DARKBLUE);
-            y_eventos += 24.0; // espacio entre líneas
+        // avanzar simulación
+        if !paused {
+            timer += dt;
+            if timer >= INTERVALO_DIA {
+                timer -= INTERVALO_DIA;
+                dia = dia.saturating_add(1);
+                sim.simular_dia(dia);
+            }
         }
 
+        let fotograma = Fotograma::de(&sim, Vec::new());
+        let cazas_recientes: Vec<String> = cazas.borrow().iter().cloned().collect();
+        let cuadro = Cuadro {
+            titulo: format!(
+                "Ecosistema - Día: {}{}  (semilla {}){}",
+                dia,
+                fecha_de(sim.calendario.as_ref(), dia),
+                sim.semilla,
+                if paused { " (PAUSADO)" } else { "" }
+            ),
+            fotograma: &fotograma,
+            cazas: &cazas_recientes,
+            ayuda: "Space: Pausa/Reanuda    R: Reiniciar    G: Guardar",
+            aviso: &aviso,
+            progreso: None,
+        };
+        dibujar(&cuadro, &especies, &mut display, dt);
+
         next_frame().await;
     }
 
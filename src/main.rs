@@ -1,42 +1,336 @@
-mod organismo;
-mod simulador;
-mod cabra;
-mod conejo;
-mod lobo;
-mod modelo;
-
-use simulador::Simulador;
-use cabra::Cabra;
-use conejo::Conejo;
-use lobo::Lobo;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::process;
+use std::rc::Rc;
 
-//Version para visualizar solo en consola
-fn main() {
-    let mut sim = Simulador::new();
-    let mut rng = rand::thread_rng();
+use juego_mejorado::ajuste;
+use juego_mejorado::barrido::Barrido;
+use juego_mejorado::calendario::Calendario;
+use juego_mejorado::argumentos::{Argumentos, Detalle, ErrorArgumentos, Formato, AYUDA};
+use juego_mejorado::edo::{self, DosPresas, HollingII};
+use juego_mejorado::escenario::Escenario;
+use juego_mejorado::estado::Estado;
+use juego_mejorado::evento::Evento;
+use juego_mejorado::grabacion::Grabacion;
+use juego_mejorado::montecarlo::MonteCarlo;
+use juego_mejorado::respuesta;
+use juego_mejorado::organismo::{Identidad, Organismo};
+use juego_mejorado::simulador::Simulador;
+
+// Escribe los eventos del día (salvo los nacimientos, que son demasiados).
+fn escribir_eventos(salida: &mut dyn Write, eventos: &[Evento], calendario: Option<&Calendario>) -> io::Result<()> {
+    for evento in eventos {
+        match evento {
+            Evento::Nacimiento { .. } => {}
+            Evento::DiaTerminado { dia, muertes, .. } => {
+                match calendario {
+                    Some(c) => writeln!(salida, "\n--- Día {} ({}) ---", dia, c.describir(*dia))?,
+                    None => writeln!(salida, "\n--- Día {} ---", dia)?,
+                }
+                writeln!(salida, "Murieron {} organismos en este día.", muertes)?;
+            }
+            otro => writeln!(salida, "{}", otro)?,
+        }
+    }
+    Ok(())
+}
+
+// Reporte por individuo al final de cada día.
+fn escribir_reporte(salida: &mut dyn Write, sim: &Simulador) -> io::Result<()> {
+    // BTreeMap para que el orden del reporte no cambie entre corridas.
+    let mut especies: BTreeMap<&str, Vec<&dyn Organismo>> = BTreeMap::new();
+    for org in sim.poblacion.iter() {
+        especies.entry(org.nombre()).or_default().push(org);
+    }
+
+    for (especie, lista) in &especies {
+        writeln!(salida, "\n {} (total: {})", especie, lista.len())?;
+        for org in lista {
+            let Identidad { id, madre, padre } = *org.identidad();
+            let linaje = match (madre, padre) {
+                (Some(m), Some(p)) => format!(", madre #{}, padre #{}", m, p),
+                (Some(m), None) => format!(", madre #{}", m),
+                _ => String::new(),
+            };
+            let lugar = match sim.espacio {
+                Some(_) => format!(", en ({}, {})", org.posicion().x, org.posicion().y),
+                None => String::new(),
+            };
+            let prenada = match org.gestando() {
+                0 => String::new(),
+                crias => format!(", preñada de {}", crias),
+            };
+            writeln!(
+                salida,
+                "   ID {} -> (edad: {} días, peso: {:.2}{}{}{})",
+                id,
+                org.edad(),
+                org.peso(),
+                linaje,
+                lugar,
+                prenada
+            )?;
+        }
+    }
+
+    writeln!(salida, "\n📊 Total población: {}\n", sim.poblacion.len())
+}
+
+// Una línea por día: individuos, nacimientos y muertes de cada especie
+// (y, para los depredadores que atacaron, cazas sobre intentos; para las
+// especies con hembras preñadas, cuántas hay).
+fn escribir_resumen(salida: &mut dyn Write, sim: &Simulador) -> io::Result<()> {
+    let Some(registro) = sim.historial.dias.last() else { return Ok(()) };
+    let especies: Vec<String> = registro.especies.iter()
+        .map(|e| {
+            let mut detalle = format!("+{} -{}", e.nacimientos, e.muertes);
+            if e.intentos_caza() > 0 {
+                detalle += &format!(", cazas {}/{}", e.cazas, e.intentos_caza());
+            }
+            if e.prenadas > 0 {
+                detalle += &format!(", preñadas {}", e.prenadas);
+            }
+            format!("{} {} ({})", e.especie, e.individuos, detalle)
+        })
+        .collect();
+    let fecha = match (registro.fecha, registro.estacion) {
+        (Some(fecha), Some(estacion)) => format!(" ({}, {})", fecha, estacion),
+        _ => String::new(),
+    };
+    writeln!(
+        salida,
+        "Día {}{}: {} | total {} | pasto {:.0} kg",
+        registro.dia,
+        fecha,
+        especies.join(", "),
+        sim.poblacion.len(),
+        registro.vegetacion
+    )
+}
+
+// Trayectoria del modelo de media de campo desde la población inicial del escenario:
+// dos presas y lobo si hay al menos dos herbívoros; si no, Holling II con la única presa.
+fn media_campo(escenario: &Escenario, dias: u32) -> Option<String> {
+    let inicial = |especie: &str| escenario.poblacion.get(especie).map_or(0.0, |p| p.total() as f64);
+    let lobos = inicial("Lobo");
+    match escenario.herbivoros.as_slice() {
+        [] => None,
+        [presa] => {
+            let modelo = HollingII::desde_escenario(escenario, &presa.nombre)?;
+            let trayectoria = edo::integrar(&modelo, [inicial(&presa.nombre), lobos], dias, 0.1);
+            Some(edo::trayectoria_csv(&trayectoria, [presa.nombre.as_str(), "Lobo"]))
+        }
+        [p1, p2, ..] => {
+            let modelo = DosPresas::desde_escenario(escenario)?;
+            let x0 = [inicial(&p1.nombre), inicial(&p2.nombre), lobos];
+            let trayectoria = edo::integrar(&modelo, x0, dias, 0.1);
+            Some(edo::trayectoria_csv(&trayectoria, [p1.nombre.as_str(), p2.nombre.as_str(), "Lobo"]))
+        }
+    }
+}
+
+// Corre las réplicas pedidas y escribe sus estadísticas en CSV en la salida.
+fn replicas(args: &Argumentos, escenario: &Escenario, replicas: u32, dias: u32) -> Result<(), Box<dyn Error>> {
+    let semilla = escenario.semilla.unwrap_or_else(rand::random);
+    let resultado = MonteCarlo::ejecutar(escenario, replicas, dias, semilla);
+
+    if let Some(ruta) = &args.extinciones {
+        std::fs::write(ruta, resultado.extinciones_csv())?;
+    }
+    let Some(ruta) = &args.salida else {
+        print!("{}", resultado.a_csv());
+        return Ok(());
+    };
+    std::fs::write(ruta, resultado.a_csv())?;
+
+    // Con los datos en un archivo, la consola muestra un resumen del final.
+    println!("Semilla base: {} | {} réplicas de {} días", semilla, replicas, dias);
+    for especie in resultado.especies() {
+        let fin = resultado.estadistica(especie, dias);
+        let tiempos = resultado.tiempos_extincion(especie);
+        let mediana = match tiempos.get(tiempos.len() / 2) {
+            Some(dia) => format!(", día mediano de extinción {}", dia),
+            None => String::new(),
+        };
+        println!(
+            "{}: media {:.1} [p05 {:.0}, p95 {:.0}] | extinta en {:.0}% de las réplicas{}",
+            especie,
+            fin.media,
+            fin.p05,
+            fin.p95,
+            fin.prob_extincion * 100.0,
+            mediana
+        );
+    }
+    Ok(())
+}
+
+// Corre el barrido de parámetros y escribe la tabla de puntos en la salida.
+fn barrido(args: &Argumentos, escenario: &Escenario, ruta: &Path, dias: u32) -> Result<(), Box<dyn Error>> {
+    let barrido = Barrido::cargar(ruta)?;
+    let semilla = escenario.semilla.unwrap_or_else(rand::random);
+    let resultado = barrido.ejecutar(escenario, dias, semilla)?;
+
+    if let (Some(ruta), Some(sensibilidad)) = (&args.sensibilidad, &resultado.sensibilidad) {
+        std::fs::write(ruta, sensibilidad.a_csv())?;
+    }
+    let Some(ruta) = &args.salida else {
+        print!("{}", resultado.a_csv());
+        return Ok(());
+    };
+    std::fs::write(ruta, resultado.a_csv())?;
 
-    // Población inicial configurable
-    let num_cabras_iniciales = 100;
-    let num_conejos_iniciales = 10;
-    let num_lobos_iniciales = 1; // 🔹 ahora varios lobos iniciales
+    // Con los datos en un archivo, la consola muestra qué parámetros pesan más.
+    println!("Semilla base: {} | {} puntos de {} días", semilla, resultado.puntos.len(), dias);
+    if let Some(sensibilidad) = &resultado.sensibilidad {
+        for metrica in &resultado.metricas {
+            let ranking: Vec<String> = sensibilidad.ranking(metrica).iter()
+                .map(|(parametro, valor)| format!("{} {:.3}", parametro, valor))
+                .collect();
+            println!("{}: {}", metrica, ranking.join(", "));
+        }
+    }
+    Ok(())
+}
 
-    // Cabras iniciales
-    for _ in 0..num_cabras_iniciales {
-        sim.agregar(Box::new(Cabra::new_random(&mut rng)));
+fn ejecutar(args: &Argumentos) -> Result<(), Box<dyn Error>> {
+    if let Some(ruta) = &args.ajustar_gompertz {
+        let resultado = ajuste::ajustar_gompertz(&ajuste::cargar_csv(ruta)?, None)?;
+        println!("{}\n\nPara el escenario:\n{}", resultado, resultado.a_toml());
+        return Ok(());
     }
 
-    // Conejos iniciales
-    for _ in 0..num_conejos_iniciales {
-        sim.agregar(Box::new(Conejo::new_random(&mut rng)));
+    let escenario = args.escenario()?;
+    let dias = escenario.dias.unwrap_or(300);
+
+    if let Some(ruta) = &args.media_campo {
+        let csv = media_campo(&escenario, dias).ok_or("el escenario no tiene presas para el modelo de media de campo")?;
+        std::fs::write(ruta, csv)?;
+    }
+    if let Some(ruta) = &args.barrido {
+        return barrido(args, &escenario, ruta, dias);
+    }
+    if let Some(n) = args.replicas {
+        return replicas(args, &escenario, n, dias);
     }
 
-    // Lobos iniciales
-    for _ in 0..num_lobos_iniciales {
-        sim.agregar(Box::new(Lobo::new())); 
+    // Una corrida guardada sigue desde su último día; `dias` son los que se agregan.
+    let mut sim = match &args.cargar {
+        Some(ruta) => Estado::cargar(ruta)?.restaurar()?,
+        None => escenario.crear_simulador(None),
+    };
+    // Para comparar, los lobos de la corrida tienen que cazar con una curva.
+    let respuesta = sim.poblacion.lobos.first().map_or(escenario.lobo.respuesta, |l| l.respuesta);
+    if args.respuesta.is_some() && respuesta.is_none() {
+        return Err("--respuesta necesita una respuesta funcional en la sección [lobo] del escenario".into());
     }
+    let inicio = sim.dia();
+    let mut grabacion = args.grabar.as_ref().map(|_| Grabacion::new(&sim));
 
-    // Simulación de varios días
-    for dia in 1..=300{ 
+    // Los eventos se juntan durante el día y se escriben al terminarlo.
+    let eventos = Rc::new(RefCell::new(Vec::new()));
+    let buzon = Rc::clone(&eventos);
+    sim.suscribir(move |evento: &Evento| buzon.borrow_mut().push(evento.clone()));
+
+    let mut salida: Box<dyn Write> = match &args.salida {
+        Some(ruta) => Box::new(BufWriter::new(File::create(ruta)?)),
+        None => Box::new(io::stdout().lock()),
+    };
+
+    // La bitácora va a la salida en formato texto. Con CSV o JSON la salida
+    // queda para los datos; la bitácora va a la consola solo si los datos van a un archivo.
+    let mut bitacora: Box<dyn Write> = match (args.formato, &args.salida) {
+        (Formato::Texto, _) => Box::new(&mut salida),
+        (_, Some(_)) => Box::new(io::stdout().lock()),
+        (_, None) => Box::new(io::sink()),
+    };
+
+    writeln!(bitacora, "Semilla: {}", sim.semilla)?;
+    if inicio > 0 {
+        writeln!(bitacora, "Continúa desde el día {}", inicio)?;
+    }
+    for dia in inicio + 1..=inicio + dias {
         sim.simular_dia(dia);
+        let del_dia = std::mem::take(&mut *eventos.borrow_mut());
+        if let Some(grabacion) = &mut grabacion {
+            let cazas = del_dia.iter()
+                .filter(|e| matches!(e, Evento::Caza { .. } | Evento::Escape { .. }))
+                .cloned()
+                .collect();
+            grabacion.agregar(&sim, cazas);
+        }
+        match args.detalle {
+            Detalle::Completo => {
+                escribir_eventos(&mut bitacora, &del_dia, sim.calendario.as_ref())?;
+                escribir_reporte(&mut bitacora, &sim)?;
+            }
+            Detalle::Resumen => escribir_resumen(&mut bitacora, &sim)?,
+        }
+    }
+    let caza = sim.historial.resumen_caza("Lobo");
+    if caza.intentos > 0 {
+        writeln!(
+            bitacora,
+            "Lobo: {} ataques, {} cazas ({:.1}% de éxito), {:.3} presas por lobo y por día",
+            caza.intentos,
+            caza.cazas,
+            100.0 * caza.exito(),
+            caza.tasa_muerte()
+        )?;
+    }
+    let especies = sim.historial.dias.last().map(|d| d.especies.as_slice()).unwrap_or_default();
+    for especie in especies.iter().map(|e| e.especie.as_str()) {
+        let (perdidas, por_caza) = sim.historial.crias_perdidas(especie);
+        if perdidas > 0 {
+            writeln!(
+                bitacora,
+                "{}: {} crías perdidas en gestación ({} por depredación de la madre)",
+                especie, perdidas, por_caza
+            )?;
+        }
+    }
+    bitacora.flush()?;
+    drop(bitacora);
+
+    match args.formato {
+        Formato::Texto => {}
+        Formato::Csv => salida.write_all(sim.historial.a_csv().as_bytes())?,
+        Formato::Json => salida.write_all(sim.historial.a_json().as_bytes())?,
+    }
+    salida.flush()?;
+
+    if let Some(ruta) = &args.guardar {
+        Estado::de(&sim).guardar(ruta)?;
+    }
+    if let (Some(ruta), Some(grabacion)) = (&args.grabar, &grabacion) {
+        grabacion.guardar(ruta)?;
+    }
+    if let Some(ruta) = &args.respuesta {
+        std::fs::write(ruta, respuesta::comparar(&sim.historial, "Lobo"))?;
+    }
+    Ok(())
+}
+
+//Version para visualizar solo en consola
+fn main() {
+    let args = match Argumentos::desde(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(ErrorArgumentos::Ayuda) => {
+            print!("{}", AYUDA);
+            return;
+        }
+        Err(e) => {
+            eprintln!("error: {}\n\nUse --ayuda para ver las opciones.", e);
+            process::exit(2);
+        }
+    };
+
+    if let Err(e) = ejecutar(&args) {
+        eprintln!("error: {}", e);
+        process::exit(1);
     }
 }
@@ -1,4 +1,4 @@
-use std::f64::consts::E;
+use serde::{Deserialize, Serialize};
 
 /// Calcula el peso de un organismo con la función Gompertz.
 /// t = edad (días)
@@ -8,3 +8,250 @@ use std::f64::consts::E;
 pub fn gompertz(t: f64, a: f64, b: f64, m: f64) -> f64 {
     a * (- (b * (m - t)).exp()).exp()
 }
+
+/// Una curva de peso por edad (edad en días, peso en kg).
+pub trait CurvaCrecimiento {
+    fn peso(&self, edad: f64) -> f64;
+    /// Derivada del peso: cuántos kg gana por día a esa edad.
+    fn tasa_crecimiento(&self, edad: f64) -> f64;
+    /// Inversa: la edad a la que se alcanza `peso` (None si la curva nunca lo toma).
+    fn edad(&self, peso: f64) -> Option<f64>;
+    /// Asíntota: el peso adulto.
+    fn peso_maximo(&self) -> f64;
+}
+
+/// Parámetros de la curva de Gompertz de una especie.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Gompertz {
+    /// A: peso máximo (kg).
+    pub peso_maximo: f64,
+    /// B: tasa de crecimiento.
+    pub tasa: f64,
+    /// M: día de inflexión.
+    pub inflexion: f64,
+}
+
+impl CurvaCrecimiento for Gompertz {
+    fn peso(&self, edad: f64) -> f64 {
+        gompertz(edad, self.peso_maximo, self.tasa, self.inflexion)
+    }
+
+    fn tasa_crecimiento(&self, edad: f64) -> f64 {
+        self.peso(edad) * self.tasa * (self.tasa * (self.inflexion - edad)).exp()
+    }
+
+    fn edad(&self, peso: f64) -> Option<f64> {
+        (peso > 0.0 && peso < self.peso_maximo)
+            .then(|| self.inflexion - (-(peso / self.peso_maximo).ln()).ln() / self.tasa)
+    }
+
+    fn peso_maximo(&self) -> f64 {
+        self.peso_maximo
+    }
+}
+
+/// Curva logística: A / (1 + e^(-B(t - M))), simétrica alrededor de M.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Logistica {
+    pub peso_maximo: f64,
+    pub tasa: f64,
+    /// Día de inflexión (se alcanza la mitad del peso máximo).
+    pub inflexion: f64,
+}
+
+impl CurvaCrecimiento for Logistica {
+    fn peso(&self, edad: f64) -> f64 {
+        self.peso_maximo / (1.0 + (-self.tasa * (edad - self.inflexion)).exp())
+    }
+
+    fn tasa_crecimiento(&self, edad: f64) -> f64 {
+        let w = self.peso(edad);
+        self.tasa * w * (1.0 - w / self.peso_maximo)
+    }
+
+    fn edad(&self, peso: f64) -> Option<f64> {
+        (peso > 0.0 && peso < self.peso_maximo)
+            .then(|| self.inflexion - (self.peso_maximo / peso - 1.0).ln() / self.tasa)
+    }
+
+    fn peso_maximo(&self) -> f64 {
+        self.peso_maximo
+    }
+}
+
+/// Von Bertalanffy en peso: A·(1 - e^(-K(t - t0)))³. Antes de t0 el peso es 0.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VonBertalanffy {
+    pub peso_maximo: f64,
+    /// K: tasa de crecimiento.
+    pub tasa: f64,
+    /// t0: edad teórica de peso cero (suele ser negativa).
+    pub edad_cero: f64,
+}
+
+impl CurvaCrecimiento for VonBertalanffy {
+    fn peso(&self, edad: f64) -> f64 {
+        let x = 1.0 - (-self.tasa * (edad - self.edad_cero)).exp();
+        self.peso_maximo * x.max(0.0).powi(3)
+    }
+
+    fn tasa_crecimiento(&self, edad: f64) -> f64 {
+        if edad <= self.edad_cero {
+            return 0.0;
+        }
+        let e = (-self.tasa * (edad - self.edad_cero)).exp();
+        3.0 * self.peso_maximo * self.tasa * e * (1.0 - e).powi(2)
+    }
+
+    fn edad(&self, peso: f64) -> Option<f64> {
+        (peso >= 0.0 && peso < self.peso_maximo)
+            .then(|| self.edad_cero - (1.0 - (peso / self.peso_maximo).cbrt()).ln() / self.tasa)
+    }
+
+    fn peso_maximo(&self) -> f64 {
+        self.peso_maximo
+    }
+}
+
+/// Richards: A·(1 + ν·e^(-B(t - M)))^(-1/ν). Generaliza a las otras:
+/// con forma ν = 1 es la logística y con ν → 0 tiende a Gompertz.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Richards {
+    pub peso_maximo: f64,
+    pub tasa: f64,
+    /// M: desplazamiento en días (la inflexión cuando ν = 1).
+    pub inflexion: f64,
+    /// ν: forma de la curva, mayor que 0.
+    pub forma: f64,
+}
+
+impl CurvaCrecimiento for Richards {
+    fn peso(&self, edad: f64) -> f64 {
+        let e = (-self.tasa * (edad - self.inflexion)).exp();
+        self.peso_maximo * (1.0 + self.forma * e).powf(-1.0 / self.forma)
+    }
+
+    fn tasa_crecimiento(&self, edad: f64) -> f64 {
+        let e = (-self.tasa * (edad - self.inflexion)).exp();
+        self.peso_maximo * self.tasa * e * (1.0 + self.forma * e).powf(-1.0 / self.forma - 1.0)
+    }
+
+    fn edad(&self, peso: f64) -> Option<f64> {
+        if !(peso > 0.0 && peso < self.peso_maximo) {
+            return None;
+        }
+        let e = ((self.peso_maximo / peso).powf(self.forma) - 1.0) / self.forma;
+        Some(self.inflexion - e.ln() / self.tasa)
+    }
+
+    fn peso_maximo(&self) -> f64 {
+        self.peso_maximo
+    }
+}
+
+/// Curva elegida por una especie. En el escenario se escribe como
+/// `crecimiento = { gompertz = { peso_maximo = 60.0, tasa = 0.01, inflexion = 150.0 } }`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Crecimiento {
+    Gompertz(Gompertz),
+    Logistica(Logistica),
+    VonBertalanffy(VonBertalanffy),
+    Richards(Richards),
+}
+
+impl Crecimiento {
+    fn curva(&self) -> &dyn CurvaCrecimiento {
+        match self {
+            Crecimiento::Gompertz(c) => c,
+            Crecimiento::Logistica(c) => c,
+            Crecimiento::VonBertalanffy(c) => c,
+            Crecimiento::Richards(c) => c,
+        }
+    }
+}
+
+impl CurvaCrecimiento for Crecimiento {
+    fn peso(&self, edad: f64) -> f64 {
+        self.curva().peso(edad)
+    }
+
+    fn tasa_crecimiento(&self, edad: f64) -> f64 {
+        self.curva().tasa_crecimiento(edad)
+    }
+
+    fn edad(&self, peso: f64) -> Option<f64> {
+        self.curva().edad(peso)
+    }
+
+    fn peso_maximo(&self) -> f64 {
+        self.curva().peso_maximo()
+    }
+}
+
+impl From<Gompertz> for Crecimiento {
+    fn from(g: Gompertz) -> Self {
+        Crecimiento::Gompertz(g)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn curvas() -> [Crecimiento; 4] {
+        [
+            Gompertz { peso_maximo: 60.0, tasa: 0.01, inflexion: 150.0 }.into(),
+            Crecimiento::Logistica(Logistica { peso_maximo: 60.0, tasa: 0.02, inflexion: 150.0 }),
+            Crecimiento::VonBertalanffy(VonBertalanffy { peso_maximo: 60.0, tasa: 0.005, edad_cero: -20.0 }),
+            Crecimiento::Richards(Richards { peso_maximo: 60.0, tasa: 0.015, inflexion: 120.0, forma: 0.4 }),
+        ]
+    }
+
+    #[test]
+    fn la_edad_deshace_el_peso() {
+        for curva in curvas() {
+            for edad in [0.0, 30.0, 150.0, 400.0, 900.0] {
+                let vuelta = curva.edad(curva.peso(edad)).unwrap();
+                assert!((vuelta - edad).abs() < 1e-6, "{:?}: {} da {}", curva, edad, vuelta);
+            }
+            // Nunca llega al peso adulto ni lo pasa.
+            assert_eq!(curva.edad(curva.peso_maximo()), None);
+            assert_eq!(curva.edad(70.0), None);
+            assert_eq!(curva.edad(-1.0), None);
+        }
+        // Von Bertalanffy sí toma el peso 0, en t0.
+        assert_eq!(curvas()[2].edad(0.0), Some(-20.0));
+        assert_eq!(curvas()[0].edad(0.0), None);
+    }
+
+    #[test]
+    fn la_tasa_de_crecimiento_es_la_derivada_del_peso() {
+        let h = 1e-3;
+        for curva in curvas() {
+            for edad in [10.0, 150.0, 500.0] {
+                let numerica = (curva.peso(edad + h) - curva.peso(edad - h)) / (2.0 * h);
+                assert!((curva.tasa_crecimiento(edad) - numerica).abs() < 1e-6, "{:?} a los {} días", curva, edad);
+            }
+        }
+    }
+
+    #[test]
+    fn richards_generaliza_a_la_logistica_y_a_gompertz() {
+        let logistica = Logistica { peso_maximo: 60.0, tasa: 0.02, inflexion: 150.0 };
+        let gompertz = Gompertz { peso_maximo: 60.0, tasa: 0.01, inflexion: 150.0 };
+        let uno = Richards { peso_maximo: 60.0, tasa: 0.02, inflexion: 150.0, forma: 1.0 };
+        let casi_cero = Richards { peso_maximo: 60.0, tasa: 0.01, inflexion: 150.0, forma: 1e-6 };
+        for edad in [0.0, 100.0, 150.0, 300.0] {
+            assert!((uno.peso(edad) - logistica.peso(edad)).abs() < 1e-9);
+            assert!((casi_cero.peso(edad) - gompertz.peso(edad)).abs() < 1e-3);
+        }
+        // En la inflexión, la mitad del peso en la logística y A/e en Gompertz.
+        assert!((logistica.peso(150.0) - 30.0).abs() < 1e-12);
+        assert!((gompertz.peso(150.0) - 60.0 / std::f64::consts::E).abs() < 1e-12);
+    }
+}
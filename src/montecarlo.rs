@@ -0,0 +1,222 @@
+use crate::escenario::Escenario;
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+/// Semilla de la réplica `i` derivada de la semilla base (SplitMix64):
+/// réplicas distintas quedan bien separadas aunque las bases sean parecidas.
+pub fn semilla_replica(base: u64, i: u32) -> u64 {
+    let mut z = base.wrapping_add((i as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Lo que se guarda de cada corrida: individuos por especie y por día
+/// (índice 0 = población inicial) y el día en que se extinguió cada especie.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replica {
+    pub semilla: u64,
+    pub conteos: BTreeMap<String, Vec<usize>>,
+    pub extincion: BTreeMap<String, Option<u32>>,
+}
+
+/// Corre una réplica del escenario con la semilla dada.
+pub fn correr_replica(escenario: &Escenario, semilla: u64, dias: u32) -> Replica {
+    let mut sim = escenario.crear_simulador(Some(semilla));
+    let especies = especies(escenario);
+
+    let censo = sim.censo();
+    let mut conteos: BTreeMap<String, Vec<usize>> = especies.iter()
+        .map(|e| (e.clone(), vec![censo.get(e).copied().unwrap_or(0)]))
+        .collect();
+    for dia in 1..=dias {
+        sim.simular_dia(dia);
+        let censo = sim.censo();
+        for (especie, serie) in &mut conteos {
+            serie.push(censo.get(especie).copied().unwrap_or(0));
+        }
+    }
+
+    let extincion = conteos.iter()
+        .map(|(e, serie)| (e.clone(), serie.iter().position(|&n| n == 0).map(|d| d as u32)))
+        .collect();
+    Replica { semilla, conteos, extincion }
+}
+
+// Especies del escenario: los herbívoros en orden y el lobo.
+fn especies(escenario: &Escenario) -> Vec<String> {
+    escenario.herbivoros.iter()
+        .map(|h| h.nombre.clone())
+        .chain(std::iter::once("Lobo".to_string()))
+        .collect()
+}
+
+/// Estadísticas de una especie en un día, sobre todas las réplicas.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EstadisticaDia {
+    pub media: f64,
+    pub mediana: f64,
+    pub p05: f64,
+    pub p25: f64,
+    pub p75: f64,
+    pub p95: f64,
+    /// Fracción de réplicas en que la especie ya se extinguió ese día.
+    pub prob_extincion: f64,
+}
+
+/// Resultado de un lote de réplicas.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonteCarlo {
+    pub dias: u32,
+    pub semilla_base: u64,
+    pub replicas: Vec<Replica>,
+}
+
+impl MonteCarlo {
+    /// Corre `replicas` corridas independientes del escenario en paralelo
+    /// (todos los núcleos), cada una con su semilla derivada de `semilla_base`.
+    /// El resultado no depende del número de hilos.
+    pub fn ejecutar(escenario: &Escenario, replicas: u32, dias: u32, semilla_base: u64) -> Self {
+        let replicas = (0..replicas)
+            .into_par_iter()
+            .map(|i| correr_replica(escenario, semilla_replica(semilla_base, i), dias))
+            .collect();
+        MonteCarlo { dias, semilla_base, replicas }
+    }
+
+    pub fn especies(&self) -> Vec<&str> {
+        self.replicas.first().map(|r| r.conteos.keys().map(String::as_str).collect()).unwrap_or_default()
+    }
+
+    /// Estadísticas de una especie para el día `dia` (0 = población inicial).
+    pub fn estadistica(&self, especie: &str, dia: u32) -> EstadisticaDia {
+        let mut valores: Vec<f64> = self.replicas.iter()
+            .map(|r| r.conteos.get(especie).and_then(|s| s.get(dia as usize)).copied().unwrap_or(0) as f64)
+            .collect();
+        valores.sort_by(f64::total_cmp);
+        let extintas = self.replicas.iter()
+            .filter(|r| r.extincion.get(especie).copied().flatten().is_some_and(|d| d <= dia))
+            .count();
+        let n = valores.len().max(1) as f64;
+        EstadisticaDia {
+            media: valores.iter().sum::<f64>() / n,
+            mediana: percentil(&valores, 50.0),
+            p05: percentil(&valores, 5.0),
+            p25: percentil(&valores, 25.0),
+            p75: percentil(&valores, 75.0),
+            p95: percentil(&valores, 95.0),
+            prob_extincion: extintas as f64 / n,
+        }
+    }
+
+    /// Días de extinción de una especie en las réplicas en que se extinguió.
+    pub fn tiempos_extincion(&self, especie: &str) -> Vec<u32> {
+        let mut tiempos: Vec<u32> = self.replicas.iter()
+            .filter_map(|r| r.extincion.get(especie).copied().flatten())
+            .collect();
+        tiempos.sort_unstable();
+        tiempos
+    }
+
+    /// Una fila por día y especie con media, mediana, bandas de percentiles
+    /// y probabilidad de extinción acumulada.
+    pub fn a_csv(&self) -> String {
+        let mut csv = String::from("dia,especie,media,mediana,p05,p25,p75,p95,prob_extincion\n");
+        for dia in 0..=self.dias {
+            for especie in self.especies() {
+                let e = self.estadistica(especie, dia);
+                let _ = writeln!(
+                    csv,
+                    "{},{},{:.4},{:.4},{:.4},{:.4},{:.4},{:.4},{:.4}",
+                    dia, especie, e.media, e.mediana, e.p05, e.p25, e.p75, e.p95, e.prob_extincion
+                );
+            }
+        }
+        csv
+    }
+
+    /// Distribución de tiempos de extinción: una fila por réplica y especie
+    /// (día vacío si la especie sobrevivió).
+    pub fn extinciones_csv(&self) -> String {
+        let mut csv = String::from("replica,semilla,especie,dia_extincion\n");
+        for (i, replica) in self.replicas.iter().enumerate() {
+            for (especie, dia) in &replica.extincion {
+                let dia = dia.map(|d| d.to_string()).unwrap_or_default();
+                let _ = writeln!(csv, "{},{},{},{}", i, replica.semilla, especie, dia);
+            }
+        }
+        csv
+    }
+}
+
+// Percentil `p` (0 a 100) de una lista ordenada, interpolando entre vecinos.
+fn percentil(ordenados: &[f64], p: f64) -> f64 {
+    match ordenados.len() {
+        0 => 0.0,
+        n => {
+            let pos = p / 100.0 * (n - 1) as f64;
+            let (abajo, arriba) = (pos.floor() as usize, pos.ceil() as usize);
+            ordenados[abajo] + (ordenados[arriba] - ordenados[abajo]) * (pos - abajo as f64)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Réplicas a mano: la réplica `i` tiene `i` conejos el día 1 y, si
+    // llega a 0, se extingue ese día.
+    fn lote(n: usize) -> MonteCarlo {
+        let replicas = (0..n).map(|i| {
+            let serie = vec![5, i, i];
+            let extincion = serie.iter().position(|&c| c == 0).map(|d| d as u32);
+            Replica {
+                semilla: i as u64,
+                conteos: BTreeMap::from([("Conejo".to_string(), serie)]),
+                extincion: BTreeMap::from([("Conejo".to_string(), extincion)]),
+            }
+        }).collect();
+        MonteCarlo { dias: 2, semilla_base: 0, replicas }
+    }
+
+    #[test]
+    fn percentiles_interpolados_entre_las_replicas() {
+        let mc = lote(11);
+        let e = mc.estadistica("Conejo", 1);
+        assert_eq!((e.media, e.mediana), (5.0, 5.0));
+        assert_eq!((e.p05, e.p25, e.p75, e.p95), (0.5, 2.5, 7.5, 9.5));
+        // El día 0 todas empiezan igual.
+        let inicio = mc.estadistica("Conejo", 0);
+        assert_eq!((inicio.p05, inicio.p95, inicio.prob_extincion), (5.0, 5.0, 0.0));
+        assert_eq!(percentil(&[], 50.0), 0.0);
+        assert_eq!(percentil(&[3.0], 95.0), 3.0);
+    }
+
+    #[test]
+    fn la_extincion_se_acumula_desde_el_dia_en_que_ocurre() {
+        let mc = lote(4);
+        assert_eq!(mc.estadistica("Conejo", 0).prob_extincion, 0.0);
+        assert_eq!(mc.estadistica("Conejo", 1).prob_extincion, 0.25);
+        assert_eq!(mc.estadistica("Conejo", 2).prob_extincion, 0.25);
+        assert_eq!(mc.tiempos_extincion("Conejo"), vec![1]);
+        assert!(mc.extinciones_csv().contains("\n0,0,Conejo,1\n1,1,Conejo,\n"));
+        assert!(mc.a_csv().contains("\n1,Conejo,1.5000,1.5000,0.1500,0.7500,2.2500,2.8500,0.2500\n"));
+    }
+
+    #[test]
+    fn las_replicas_se_repiten_con_la_misma_semilla_base() {
+        let escenario = Escenario::default();
+        let mc = MonteCarlo::ejecutar(&escenario, 4, 30, 7);
+        assert_eq!(mc, MonteCarlo::ejecutar(&escenario, 4, 30, 7));
+        assert_eq!(mc.especies(), ["Cabra", "Conejo", "Lobo"]);
+        for (i, replica) in mc.replicas.iter().enumerate() {
+            assert_eq!(replica, &correr_replica(&escenario, semilla_replica(7, i as u32), 30));
+            assert!(replica.conteos.values().all(|serie| serie.len() == 31));
+        }
+        // Cada réplica con su propia semilla.
+        let semillas: std::collections::HashSet<u64> = mc.replicas.iter().map(|r| r.semilla).collect();
+        assert_eq!(semillas.len(), 4);
+    }
+}
@@ -1,30 +1,101 @@
-use rand::rngs::ThreadRng;
-use std::any::Any;
+use crate::apareamiento::SistemaApareamiento;
+use crate::calendario::Forzamiento;
+use crate::espacio::{Movimiento, Posicion};
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Generador de números aleatorios de toda la simulación.
+/// Es sembrable, así que la misma semilla reproduce la misma corrida.
+pub type Generador = ChaCha8Rng;
 
 //LO ingreso para manejar sexos entre las presas
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub enum Sexo {
     Macho,
     Hembra,
 }
 
+impl Sexo {
+    // Macho o hembra con probabilidad 50/50.
+    pub fn aleatorio(rng: &mut Generador) -> Self {
+        if rng.random_bool(0.5) { Sexo::Macho } else { Sexo::Hembra }
+    }
+}
+
+/// Por qué murió un organismo.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum CausaMuerte {
+    Vejez,
+    Enfermedad,
+    Hambre,
+    Depredacion,
+}
+
+impl fmt::Display for CausaMuerte {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let texto = match self {
+            CausaMuerte::Vejez => "vejez",
+            CausaMuerte::Enfermedad => "enfermedad",
+            CausaMuerte::Hambre => "hambre",
+            CausaMuerte::Depredacion => "depredación",
+        };
+        write!(f, "{}", texto)
+    }
+}
+
+/// Identidad permanente de un individuo: su ID único y los de sus padres.
+/// El simulador asigna el `id` al agregar el organismo o cuando nace.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Identidad {
+    pub id: u64,
+    pub madre: Option<u64>,
+    pub padre: Option<u64>,
+}
+
+impl Identidad {
+    // Identidad de una cría: el ID lo pone el simulador al registrarla.
+    pub fn cria_de(madre: u64, padre: Option<u64>) -> Self {
+        Identidad { id: 0, madre: Some(madre), padre }
+    }
+}
+
 pub trait Organismo {
-    fn envejecer(&mut self);
-    fn reproducirse(&self, rng: &mut ThreadRng) -> Vec<Box<dyn Organismo>>;
+    // Pasa un día; `forzamiento` es cómo la estación cambia sus tasas.
+    fn envejecer(&mut self, forzamiento: &Forzamiento, rng: &mut Generador);
     fn peso(&self) -> f64;
     fn esta_vivo(&self) -> bool;
     fn nombre(&self) -> &str;
     fn edad(&self) -> u32;
+    fn sexo(&self) -> Sexo;
+    // Si ya tiene edad para reproducirse.
+    fn es_maduro(&self) -> bool;
+    // Cómo se aparea la especie (ver `elegir_apareadas` en el simulador).
+    fn apareamiento(&self) -> SistemaApareamiento {
+        SistemaApareamiento::Monogamia
+    }
+    fn identidad(&self) -> &Identidad;
+    fn identidad_mut(&mut self) -> &mut Identidad;
+    // Dónde está en la grilla (solo importa si el mundo tiene espacio).
+    fn posicion(&self) -> Posicion;
+    fn posicion_mut(&mut self) -> &mut Posicion;
+    // Cómo se mueve y cuántas celdas avanza por día.
+    fn movimiento(&self) -> Movimiento {
+        Movimiento::Aleatorio
+    }
+    fn velocidad(&self) -> u32 {
+        1
+    }
 
-
-    //Convertir referencia aun trait
-    fn as_any(&self) -> &dyn Any;
-    fn as_any_mut(&mut self) -> &mut dyn Any;
-}
-
-//Para verificar tipo de organismo
-impl dyn Organismo {
-    pub fn is<T: 'static>(&self) -> bool {
-        self.as_any().is::<T>()
+    // ID permanente del individuo.
+    fn id(&self) -> u64 {
+        self.identidad().id
+    }
+    // Causa de muerte, si el organismo murió por sí mismo (vejez, enfermedad, hambre).
+    fn causa_muerte(&self) -> Option<CausaMuerte>;
+    // Crías que lleva en gestación: se pierden si muere.
+    fn gestando(&self) -> u32 {
+        0
     }
 }
@@ -0,0 +1,134 @@
+use crate::herbivoro::{Herbivoro, ParametrosHerbivoro};
+use crate::lobo::Lobo;
+use crate::organismo::Organismo;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Los individuos de una especie herbívora, uno al lado del otro en memoria.
+pub struct TablaHerbivoros {
+    pub especie: Arc<ParametrosHerbivoro>,
+    /// Ordenados por ID: los nuevos siempre entran al final.
+    pub individuos: Vec<Herbivoro>,
+}
+
+impl TablaHerbivoros {
+    pub fn new(especie: Arc<ParametrosHerbivoro>) -> Self {
+        TablaHerbivoros { especie, individuos: Vec::new() }
+    }
+
+    /// Busca un individuo por su ID (la tabla está ordenada por ID).
+    pub fn buscar(&self, id: u64) -> Option<&Herbivoro> {
+        let i = self.individuos.binary_search_by_key(&id, |h| h.identidad.id).ok()?;
+        Some(&self.individuos[i])
+    }
+}
+
+/// Todos los organismos del simulador, guardados por tipo y especie: una
+/// tabla por especie herbívora (en el orden en que aparecieron) y una de
+/// lobos. Sin una caja por individuo ni conversiones para saber qué es cada
+/// uno; el censo sale directo del largo de cada tabla.
+#[derive(Default)]
+pub struct Poblacion {
+    pub herbivoros: Vec<TablaHerbivoros>,
+    /// Ordenados por ID, como los herbívoros de cada tabla.
+    pub lobos: Vec<Lobo>,
+}
+
+impl Poblacion {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.herbivoros.iter().map(|t| t.individuos.len()).sum::<usize>() + self.lobos.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Todos los organismos: los herbívoros especie por especie y después los lobos.
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Organismo> {
+        self.herbivoros()
+            .map(|h| h as &dyn Organismo)
+            .chain(self.lobos.iter().map(|l| l as &dyn Organismo))
+    }
+
+    /// Los herbívoros de todas las especies.
+    pub fn herbivoros(&self) -> impl Iterator<Item = &Herbivoro> {
+        self.herbivoros.iter().flat_map(|t| &t.individuos)
+    }
+
+    /// La tabla de una especie herbívora, si alguna vez tuvo individuos.
+    pub fn tabla(&self, especie: &str) -> Option<&TablaHerbivoros> {
+        self.herbivoros.iter().find(|t| t.especie.nombre == especie)
+    }
+
+    // La tabla de la especie; si no existe, la crea al final.
+    fn tabla_mut(&mut self, especie: &Arc<ParametrosHerbivoro>) -> &mut TablaHerbivoros {
+        let i = match self.herbivoros.iter()
+            .position(|t| Arc::ptr_eq(&t.especie, especie) || t.especie.nombre == especie.nombre) {
+            Some(i) => i,
+            None => {
+                self.herbivoros.push(TablaHerbivoros::new(Arc::clone(especie)));
+                self.herbivoros.len() - 1
+            }
+        };
+        &mut self.herbivoros[i]
+    }
+
+    /// Busca un organismo por su ID (cada tabla está ordenada por ID).
+    pub fn buscar(&self, id: u64) -> Option<&dyn Organismo> {
+        if let Ok(i) = self.lobos.binary_search_by_key(&id, |l| l.identidad.id) {
+            return Some(&self.lobos[i]);
+        }
+        self.herbivoros.iter().find_map(|t| t.buscar(id).map(|h| h as &dyn Organismo))
+    }
+
+    pub fn contar_especie(&self, especie: &str) -> usize {
+        match especie {
+            "Lobo" => self.lobos.len(),
+            _ => self.tabla(especie).map_or(0, |t| t.individuos.len()),
+        }
+    }
+
+    /// Cantidad de organismos por especie presente, en orden alfabético.
+    pub fn censo(&self) -> BTreeMap<String, usize> {
+        let mut censo: BTreeMap<String, usize> = self.herbivoros.iter()
+            .filter(|t| !t.individuos.is_empty())
+            .map(|t| (t.especie.nombre.clone(), t.individuos.len()))
+            .collect();
+        if !self.lobos.is_empty() {
+            censo.insert("Lobo".to_string(), self.lobos.len());
+        }
+        censo
+    }
+}
+
+/// Los tipos de organismo que la población sabe guardar en sus tablas.
+pub trait Habitante: Organismo + Sized {
+    /// Lo pone al final de la tabla que le corresponde.
+    fn ubicar(self, poblacion: &mut Poblacion);
+    /// Cuántos de este tipo hay.
+    fn contar(poblacion: &Poblacion) -> usize;
+}
+
+impl Habitante for Herbivoro {
+    fn ubicar(self, poblacion: &mut Poblacion) {
+        poblacion.tabla_mut(&self.especie).individuos.push(self);
+    }
+
+    fn contar(poblacion: &Poblacion) -> usize {
+        poblacion.herbivoros.iter().map(|t| t.individuos.len()).sum()
+    }
+}
+
+impl Habitante for Lobo {
+    fn ubicar(self, poblacion: &mut Poblacion) {
+        poblacion.lobos.push(self);
+    }
+
+    fn contar(poblacion: &Poblacion) -> usize {
+        poblacion.lobos.len()
+    }
+}
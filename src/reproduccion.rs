@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+
+/// Ciclo reproductivo de las hembras de una especie: celo, concepción,
+/// gestación, parto y lactancia (anestro) hasta el siguiente celo.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Ciclo {
+    /// Probabilidad de concebir en un día de celo en que la cubre un macho.
+    pub concepcion: f64,
+    /// Días de preñez, de la concepción al parto.
+    pub gestacion: u32,
+    /// Días mínimos entre dos partos. Lo que pasa de la gestación es
+    /// lactancia: la hembra no entra en celo.
+    pub intervalo_partos: u32,
+}
+
+impl Ciclo {
+    // Días de lactancia después de cada parto.
+    fn lactancia(&self) -> u32 {
+        self.intervalo_partos.saturating_sub(self.gestacion)
+    }
+}
+
+/// En qué parte del ciclo está una hembra. Los machos y las hembras sin
+/// ciclo se quedan siempre en celo: se aparean cuando les toca.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EstadoReproductivo {
+    #[default]
+    Celo,
+    /// La camada se decide al concebir; pare cuando `faltan` llega a 0.
+    Prenada { padre: u64, camada: u32, faltan: u32 },
+    Lactancia { faltan: u32 },
+}
+
+/// Una camada que llegó a término.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Parto {
+    pub padre: u64,
+    pub camada: u32,
+}
+
+impl EstadoReproductivo {
+    pub fn en_celo(&self) -> bool {
+        matches!(self, EstadoReproductivo::Celo)
+    }
+
+    /// Crías que lleva en gestación (0 si no está preñada).
+    pub fn gestando(&self) -> u32 {
+        match self {
+            EstadoReproductivo::Prenada { camada, .. } => *camada,
+            _ => 0,
+        }
+    }
+
+    /// Queda preñada de `padre` con una camada de `camada` crías.
+    pub fn concebir(&mut self, ciclo: &Ciclo, padre: u64, camada: u32) {
+        *self = EstadoReproductivo::Prenada { padre, camada, faltan: ciclo.gestacion };
+    }
+
+    /// Pasa un día del ciclo. Si termina la gestación devuelve el parto y
+    /// empieza la lactancia; al terminar la lactancia vuelve el celo.
+    pub fn avanzar(&mut self, ciclo: &Ciclo) -> Option<Parto> {
+        match *self {
+            EstadoReproductivo::Celo => None,
+            EstadoReproductivo::Prenada { padre, camada, faltan } => {
+                if faltan > 1 {
+                    *self = EstadoReproductivo::Prenada { padre, camada, faltan: faltan - 1 };
+                    return None;
+                }
+                *self = despues_de(ciclo.lactancia());
+                Some(Parto { padre, camada })
+            }
+            // Un estado guardado a mano puede traer `faltan = 0`: ya le toca el celo.
+            EstadoReproductivo::Lactancia { faltan } => {
+                *self = despues_de(faltan.saturating_sub(1));
+                None
+            }
+        }
+    }
+}
+
+// Lactancia por `faltan` días más, o celo si ya no queda ninguno.
+fn despues_de(faltan: u32) -> EstadoReproductivo {
+    if faltan == 0 { EstadoReproductivo::Celo } else { EstadoReproductivo::Lactancia { faltan } }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn el_ciclo_pasa_por_gestacion_parto_y_lactancia() {
+        let ciclo = Ciclo { concepcion: 1.0, gestacion: 5, intervalo_partos: 8 };
+        let mut estado = EstadoReproductivo::default();
+        assert!(estado.en_celo());
+        assert_eq!(estado.avanzar(&ciclo), None);
+        assert!(estado.en_celo());
+
+        estado.concebir(&ciclo, 7, 3);
+        assert_eq!(estado.gestando(), 3);
+        assert!(!estado.en_celo());
+        for _ in 1..5 {
+            assert_eq!(estado.avanzar(&ciclo), None);
+            assert_eq!(estado.gestando(), 3);
+        }
+        // Pare a los `gestacion` días de concebir.
+        assert_eq!(estado.avanzar(&ciclo), Some(Parto { padre: 7, camada: 3 }));
+        assert_eq!(estado, EstadoReproductivo::Lactancia { faltan: 3 });
+        assert_eq!(estado.gestando(), 0);
+        // Entre los dos partos pasa por lo menos `intervalo_partos`.
+        for _ in 0..3 {
+            assert!(!estado.en_celo());
+            assert_eq!(estado.avanzar(&ciclo), None);
+        }
+        assert!(estado.en_celo());
+    }
+
+    #[test]
+    fn sin_lactancia_vuelve_al_celo_al_parir() {
+        let ciclo = Ciclo { concepcion: 1.0, gestacion: 2, intervalo_partos: 1 };
+        let mut estado = EstadoReproductivo::default();
+        estado.concebir(&ciclo, 1, 2);
+        assert_eq!(estado.avanzar(&ciclo), None);
+        assert!(estado.avanzar(&ciclo).is_some());
+        assert!(estado.en_celo());
+
+        // Una lactancia guardada sin días restantes termina en el acto.
+        let mut guardado = EstadoReproductivo::Lactancia { faltan: 0 };
+        assert_eq!(guardado.avanzar(&ciclo), None);
+        assert!(guardado.en_celo());
+    }
+}
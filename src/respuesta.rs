@@ -0,0 +1,206 @@
+use crate::historial::Historial;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+
+/// Respuesta funcional de un depredador: cuántas presas ataca por día
+/// según cuántas tiene a su alcance (`presas`) y cuántos depredadores
+/// cazan en el mismo lugar (`depredadores`, contándose a sí mismo).
+pub trait RespuestaFuncional {
+    fn ataques(&self, presas: f64, depredadores: f64) -> f64;
+}
+
+/// Tipo I: proporcional a las presas hasta un máximo por día.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HollingI {
+    /// Tasa de ataque: presas atacadas por día por cada presa disponible.
+    pub ataque: f64,
+    /// Sin límite si no se da.
+    #[serde(default)]
+    pub maximo: Option<f64>,
+}
+
+impl RespuestaFuncional for HollingI {
+    fn ataques(&self, presas: f64, _depredadores: f64) -> f64 {
+        let ataques = self.ataque * presas;
+        self.maximo.map_or(ataques, |maximo| ataques.min(maximo))
+    }
+}
+
+/// Tipo II: a·N / (1 + a·h·N). El tiempo de manipulación `h` (días por
+/// presa) satura la caza en 1/h presas por día.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HollingII {
+    pub ataque: f64,
+    pub manipulacion: f64,
+}
+
+impl RespuestaFuncional for HollingII {
+    fn ataques(&self, presas: f64, _depredadores: f64) -> f64 {
+        self.ataque * presas / (1.0 + self.ataque * self.manipulacion * presas)
+    }
+}
+
+/// Tipo III: a·N² / (1 + a·h·N²). Con pocas presas casi no las busca
+/// (sigmoide); con muchas se satura en 1/h como el tipo II.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HollingIII {
+    pub ataque: f64,
+    pub manipulacion: f64,
+}
+
+impl RespuestaFuncional for HollingIII {
+    fn ataques(&self, presas: f64, _depredadores: f64) -> f64 {
+        let encuentros = self.ataque * presas * presas;
+        encuentros / (1.0 + self.manipulacion * encuentros)
+    }
+}
+
+/// Beddington-DeAngelis: a·N / (1 + a·h·N + c·(P − 1)). Cada uno de los
+/// otros depredadores cercanos le quita tiempo de búsqueda (interferencia `c`).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BeddingtonDeAngelis {
+    pub ataque: f64,
+    pub manipulacion: f64,
+    pub interferencia: f64,
+}
+
+impl RespuestaFuncional for BeddingtonDeAngelis {
+    fn ataques(&self, presas: f64, depredadores: f64) -> f64 {
+        let otros = (depredadores - 1.0).max(0.0);
+        self.ataque * presas / (1.0 + self.ataque * self.manipulacion * presas + self.interferencia * otros)
+    }
+}
+
+/// Respuesta funcional elegida en el escenario, por ejemplo
+/// `respuesta = { holling_ii = { ataque = 0.01, manipulacion = 2.0 } }`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Respuesta {
+    #[serde(rename = "holling_i")]
+    HollingI(HollingI),
+    #[serde(rename = "holling_ii")]
+    HollingII(HollingII),
+    #[serde(rename = "holling_iii")]
+    HollingIII(HollingIII),
+    BeddingtonDeAngelis(BeddingtonDeAngelis),
+}
+
+impl Respuesta {
+    fn respuesta(&self) -> &dyn RespuestaFuncional {
+        match self {
+            Respuesta::HollingI(r) => r,
+            Respuesta::HollingII(r) => r,
+            Respuesta::HollingIII(r) => r,
+            Respuesta::BeddingtonDeAngelis(r) => r,
+        }
+    }
+}
+
+impl RespuestaFuncional for Respuesta {
+    fn ataques(&self, presas: f64, depredadores: f64) -> f64 {
+        self.respuesta().ataques(presas, depredadores)
+    }
+}
+
+/// Compara, día por día, lo que atacó y cazó en promedio cada depredador
+/// que salió a cazar con lo que le daba su curva para las presas y los
+/// depredadores que tenía a su alcance en ese momento (con espacio, los de
+/// su vecindario; sin espacio, los de todo el mundo). `presas` y
+/// `depredadores` son los promedios entre los que salieron. En CSV.
+pub fn comparar(historial: &Historial, depredador: &str) -> String {
+    let mut csv = String::from("dia,presas,depredadores,ataques_por_depredador,cazas_por_depredador,ataques_teoricos\n");
+    for dia in &historial.dias {
+        let Some(registro) = dia.especies.iter().find(|e| e.especie == depredador) else { continue };
+        if registro.busquedas == 0 {
+            continue;
+        }
+        let por_depredador = |total: f64| total / registro.busquedas as f64;
+        let _ = writeln!(
+            csv,
+            "{},{:.2},{:.2},{:.4},{:.4},{:.4}",
+            dia.dia,
+            por_depredador(registro.presas_al_alcance as f64),
+            por_depredador(registro.depredadores_cerca as f64),
+            por_depredador(registro.intentos_caza() as f64),
+            por_depredador(registro.cazas as f64),
+            por_depredador(registro.ataques_esperados),
+        );
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::argumentos::PoblacionInicial;
+    use crate::escenario::Escenario;
+    use crate::lobo::ParametrosLobo;
+
+    fn cerca(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn cada_curva_tiene_su_forma() {
+        let i = HollingI { ataque: 0.1, maximo: Some(3.0) };
+        assert!(cerca(i.ataques(20.0, 1.0), 2.0));
+        assert!(cerca(i.ataques(100.0, 1.0), 3.0));
+        assert!(cerca(HollingI { maximo: None, ..i }.ataques(100.0, 1.0), 10.0));
+
+        // El tipo II se satura en 1/h.
+        let ii = HollingII { ataque: 0.1, manipulacion: 0.5 };
+        assert!(cerca(ii.ataques(10.0, 1.0), 1.0 / 1.5));
+        assert!(ii.ataques(1e6, 1.0) < 2.0 && ii.ataques(1e6, 1.0) > 1.99);
+
+        // El tipo III ataca una fracción de las presas que crece con ellas
+        // mientras son pocas, y también se satura en 1/h.
+        let iii = HollingIII { ataque: 0.1, manipulacion: 0.5 };
+        assert!(iii.ataques(2.0, 1.0) / 2.0 < iii.ataques(4.0, 1.0) / 4.0);
+        assert!(iii.ataques(1e4, 1.0) < 2.0 && iii.ataques(1e4, 1.0) > 1.99);
+
+        // Beddington-DeAngelis: solo es el tipo II; con compañía ataca menos.
+        let bd = BeddingtonDeAngelis { ataque: 0.1, manipulacion: 0.5, interferencia: 0.2 };
+        assert!(cerca(bd.ataques(10.0, 1.0), ii.ataques(10.0, 1.0)));
+        assert!(cerca(bd.ataques(10.0, 6.0), 1.0 / 2.5));
+        assert!(bd.ataques(10.0, 3.0) > bd.ataques(10.0, 6.0));
+        assert_eq!(Respuesta::BeddingtonDeAngelis(bd).ataques(10.0, 6.0), bd.ataques(10.0, 6.0));
+    }
+
+    #[test]
+    fn la_respuesta_se_elige_en_el_escenario() {
+        let respuesta: Respuesta = toml::from_str("holling_ii = { ataque = 0.01, manipulacion = 2.0 }").unwrap();
+        assert_eq!(respuesta, Respuesta::HollingII(HollingII { ataque: 0.01, manipulacion: 2.0 }));
+        let respuesta: Respuesta = toml::from_str("holling_i = { ataque = 0.01 }").unwrap();
+        assert_eq!(respuesta, Respuesta::HollingI(HollingI { ataque: 0.01, maximo: None }));
+        assert!(toml::from_str::<Respuesta>("holling_iv = { ataque = 0.01 }").is_err());
+    }
+
+    #[test]
+    fn los_lobos_atacan_lo_que_da_su_curva() {
+        let respuesta = Respuesta::HollingII(HollingII { ataque: 0.05, manipulacion: 0.5 });
+        let mut escenario = Escenario {
+            lobo: ParametrosLobo { respuesta: Some(respuesta), edad_minima_presa: 0, ..Default::default() },
+            ..Escenario::default()
+        };
+        escenario.poblacion.insert("Cabra".to_string(), PoblacionInicial { aleatorios: 600, ..Default::default() });
+        escenario.poblacion.insert("Lobo".to_string(), PoblacionInicial { aleatorios: 5, ..Default::default() });
+        let mut sim = escenario.crear_simulador(Some(4));
+        for dia in 1..=30 {
+            sim.simular_dia(dia);
+        }
+
+        let csv = comparar(&sim.historial, "Lobo");
+        let filas: Vec<Vec<f64>> = csv.lines().skip(1)
+            .map(|fila| fila.split(',').map(|c| c.parse().unwrap()).collect())
+            .collect();
+        assert_eq!(filas.len(), 30);
+        let total = |columna: usize| filas.iter().map(|f| f[columna]).sum::<f64>();
+        // Sin fallos, cada ataque es una caza; y en promedio atacan lo que da la curva.
+        assert!(cerca(total(3), total(4)));
+        assert!((total(3) / total(5) - 1.0).abs() < 0.1, "{} ataques, {} esperados", total(3), total(5));
+    }
+}
@@ -1,144 +1,1102 @@
-use rand::Rng;
-use crate::organismo::Organismo;
-use std::collections::{HashMap, VecDeque};
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::collections::BTreeMap;
+use crate::apareamiento::SistemaApareamiento;
+use crate::calendario::{Calendario, Fecha, Forzamiento, Temporada};
+use crate::caza::{Estrategia, EstrategiaCaza, Oferta};
+use crate::respuesta::RespuestaFuncional;
+use crate::evento::{Evento, Suscriptor};
+use crate::historial::Historial;
+use crate::espacio::{Espacio, Movimiento, Posicion, Rejilla};
+use crate::lobo::Lobo;
+use crate::organismo::{CausaMuerte, Generador, Identidad, Organismo, Sexo};
+use crate::poblacion::{Habitante, Poblacion, TablaHerbivoros};
+use crate::vegetacion::Vegetacion;
 
 pub struct Simulador {
-    // Un vector que puede contener cualquier tipo que implemente `Organismo`.
-    pub poblacion: Vec<Box<dyn Organismo>>,
-    // Una cola para almacenar los eventos de caza y mostrarlos en la UI.
-    pub eventos: VecDeque<String>,
+    // Todos los organismos, en una tabla por tipo y especie.
+    pub poblacion: Poblacion,
+    // Quienes reciben los eventos que emite el motor.
+    suscriptores: Vec<Box<dyn Suscriptor>>,
+    // Semilla con la que se creó el generador (para repetir la corrida).
+    pub semilla: u64,
+    // Único generador aleatorio; se pasa a cada organismo.
+    pub rng: Generador,
+    // Pastizal del que comen todos los herbívoros.
+    pub vegetacion: Vegetacion,
+    // Grilla del mundo. Sin ella, cualquier lobo alcanza a cualquier presa
+    // y cualquier macho a cualquier hembra.
+    pub espacio: Option<Espacio>,
+    // Fecha de cada día y estaciones. Sin él, todas las tasas son iguales todo el año.
+    pub calendario: Option<Calendario>,
+    // Serie temporal de la corrida (conteos, nacimientos, muertes, pesos, reservas).
+    pub historial: Historial,
+    // Próximo ID libre; cada organismo recibe uno al entrar y nunca se reutiliza.
+    pub(crate) siguiente_id: u64,
 }
 
 impl Simulador {
+    // Crea un simulador con una semilla aleatoria.
     pub fn new() -> Self {
+        Self::con_semilla(rand::random())
+    }
+
+    // Crea un simulador reproducible: misma semilla y misma población inicial
+    // dan la misma historia día a día.
+    pub fn con_semilla(semilla: u64) -> Self {
         Simulador {
-            poblacion: Vec::new(),
-            eventos: VecDeque::with_capacity(30),
+            poblacion: Poblacion::new(),
+            suscriptores: Vec::new(),
+            semilla,
+            rng: Generador::seed_from_u64(semilla),
+            vegetacion: Vegetacion::default(),
+            espacio: None,
+            calendario: None,
+            historial: Historial::new(),
+            siguiente_id: 1,
         }
     }
 
-    pub fn registrar_evento(&mut self, mensaje: String) {
-        // Agrega un evento a la cola, eliminando el más antiguo si la capacidad está llena.
-        if self.eventos.len() == 30 {
-            self.eventos.pop_front(); 
+    // Registra un suscriptor que recibirá todos los eventos desde ahora.
+    pub fn suscribir(&mut self, suscriptor: impl Suscriptor + 'static) {
+        self.suscriptores.push(Box::new(suscriptor));
+    }
+
+    // Entrega un evento a todos los suscriptores.
+    fn emitir(&mut self, evento: Evento) {
+        self.historial.anotar(&evento);
+        for s in &mut self.suscriptores {
+            s.notificar(&evento);
         }
-        self.eventos.push_back(mensaje); 
     }
 
-    // Añade un nuevo organismo a la población del simulador.
-    pub fn agregar(&mut self, organismo: Box<dyn Organismo>) {
-        self.poblacion.push(organismo);
+    // Añade un nuevo organismo a la tabla de su especie y le asigna su ID.
+    // Devuelve el ID asignado.
+    pub fn agregar<T: Habitante>(&mut self, mut organismo: T) -> u64 {
+        let id = self.siguiente_id;
+        self.siguiente_id += 1;
+        organismo.identidad_mut().id = id;
+        organismo.ubicar(&mut self.poblacion);
+        id
+    }
+
+    // Último día simulado (0 si todavía no empezó).
+    pub fn dia(&self) -> u32 {
+        self.historial.dias.last().map_or(0, |r| r.dia)
+    }
+
+    // Busca un organismo vivo por su ID.
+    pub fn buscar(&self, id: u64) -> Option<&dyn Organismo> {
+        self.poblacion.buscar(id)
+    }
+
+    // Cuántos organismos de un tipo (`Herbivoro` o `Lobo`) hay en la población.
+    pub fn contar<T: Habitante>(&self) -> usize {
+        T::contar(&self.poblacion)
+    }
+
+    // Cuántos organismos de una especie (por nombre) hay en la población.
+    pub fn contar_especie(&self, especie: &str) -> usize {
+        self.poblacion.contar_especie(especie)
+    }
+
+    // Cantidad de organismos por especie, en orden alfabético.
+    pub fn censo(&self) -> BTreeMap<String, usize> {
+        self.poblacion.censo()
     }
 
-    // Cuenta cuántos organismos de un tipo específico (`T`) hay en la población.
-    pub fn contar<T: 'static>(&self) -> usize {
-        self.poblacion
-            .iter()
-            .filter(|o| o.as_any().is::<T>())
-            .count()
+    // Activa el mundo en grilla y reparte el pasto entre sus celdas.
+    pub fn con_espacio(mut self, espacio: Espacio) -> Self {
+        self.vegetacion = Vegetacion::en_celdas(self.vegetacion.parametros, espacio.celdas());
+        self.espacio = Some(espacio);
+        self
     }
 
+    // Activa el calendario: fechas, estaciones y temporadas de cría.
+    pub fn con_calendario(mut self, calendario: Calendario) -> Self {
+        self.calendario = Some(calendario);
+        self
+    }
+
+    // El pasto rebrota y los herbívoros comen de su celda. Si no alcanza
+    // para todos, lo disponible se reparte en proporción a lo que pide cada uno.
+    fn pastar(&mut self, dia: u32, rebrote: f64) {
+        self.vegetacion.crecer(rebrote);
+        let espacio = self.espacio;
+        let celda = |p: Posicion| espacio.map_or(0, |e| e.celda(p));
+
+        let mut demandas = vec![0.0; self.vegetacion.celdas.len()];
+        for herbivoro in self.poblacion.herbivoros() {
+            demandas[celda(herbivoro.posicion)] += herbivoro.demanda();
+        }
+        let fracciones: Vec<f64> = demandas.iter().enumerate()
+            .map(|(celda, &pedido)| {
+                if pedido > 0.0 { self.vegetacion.pastorear(celda, pedido) / pedido } else { 1.0 }
+            })
+            .collect();
+        for tabla in &mut self.poblacion.herbivoros {
+            for herbivoro in &mut tabla.individuos {
+                herbivoro.comer(fracciones[celda(herbivoro.posicion)]);
+            }
+        }
+
+        let demanda: f64 = demandas.iter().sum();
+        let consumo: f64 = demandas.iter().zip(&fracciones).map(|(d, f)| d * f).sum();
+        let biomasa = self.vegetacion.biomasa();
+        self.emitir(Evento::Pastoreo { dia, demanda, consumo, biomasa });
+    }
+
+    // Con espacio, cada organismo da tantos pasos como su velocidad según su
+    // regla de movimiento. Lo que ve cada uno es la foto de antes de moverse.
+    fn mover(&mut self) {
+        let Some(espacio) = self.espacio else { return };
+        let vision = espacio.radio_vision;
+
+        // Los mapas de "lo más cercano a la vista" se arman una vez por día
+        // y solo si alguien los usa.
+        let huye = |m: Movimiento| m == Movimiento::HuirDepredadores;
+        let alguien_huye = self.poblacion.herbivoros.iter().any(|t| huye(t.especie.movimiento))
+            || self.poblacion.lobos.iter().any(|l| huye(l.movimiento));
+        let lobos = match alguien_huye {
+            true => espacio.mas_cercanas(self.poblacion.lobos.iter().map(|l| l.posicion), vision),
+            false => Vec::new(),
+        };
+        let mut presas: Vec<(u32, Vec<Option<Posicion>>)> = Vec::new();
+        for lobo in &self.poblacion.lobos {
+            let edad_minima = lobo.edad_minima_presa;
+            if lobo.movimiento == Movimiento::HaciaComida && !presas.iter().any(|(edad, _)| *edad == edad_minima) {
+                let visibles = self.poblacion.herbivoros().filter(|h| h.edad >= edad_minima).map(|h| h.posicion);
+                presas.push((edad_minima, espacio.mas_cercanas(visibles, vision)));
+            }
+        }
+
+        let vista = Vista { espacio, vegetacion: &self.vegetacion, lobos, presas };
+        let rng = &mut self.rng;
+        for tabla in &mut self.poblacion.herbivoros {
+            let (movimiento, velocidad) = (tabla.especie.movimiento, tabla.especie.velocidad);
+            for herbivoro in &mut tabla.individuos {
+                for _ in 0..velocidad {
+                    herbivoro.posicion = vista.paso(herbivoro.posicion, movimiento, None, rng);
+                }
+            }
+        }
+        for lobo in &mut self.poblacion.lobos {
+            for _ in 0..lobo.velocidad {
+                lobo.posicion = vista.paso(lobo.posicion, lobo.movimiento, Some(lobo.edad_minima_presa), rng);
+            }
+        }
+    }
+
+
     // Simula un día completo en el ecosistema.
     pub fn simular_dia(&mut self, dia: u32) {
-        let mut rng = rand::thread_rng();
-        let mut nuevos = Vec::new();
+        let forzamiento = self.calendario.map_or_else(Forzamiento::default, |c| c.forzamiento(dia));
+        let fecha = self.calendario.map(|c| c.fecha(dia));
+        self.mover();
+        self.pastar(dia, forzamiento.rebrote);
+
+        let rng = &mut self.rng;
+        let mut pendientes = Vec::new();
         let mut muertos = 0;
 
-        // ENVEJECER Y REPRODUCCIÓN
-        self.poblacion.retain_mut(|org| {
-            org.envejecer();
-            nuevos.extend(org.reproducirse(&mut rng));
+        // ENVEJECER
+        for tabla in &mut self.poblacion.herbivoros {
+            tabla.individuos.retain_mut(|herbivoro| {
+                herbivoro.envejecer(&forzamiento, rng);
+                sigue_vivo(herbivoro, dia, &mut muertos, &mut pendientes)
+            });
+        }
+        self.poblacion.lobos.retain_mut(|lobo| {
+            lobo.envejecer(&forzamiento, rng);
+            if lobo.vivo {
+                pendientes.push(Evento::Alimentacion {
+                    dia,
+                    id: lobo.id(),
+                    especie: lobo.nombre().to_string(),
+                    consumo: lobo.ultimo_consumo,
+                    reserva: lobo.reserva,
+                });
+            }
+            sigue_vivo(lobo, dia, &mut muertos, &mut pendientes)
+        });
 
-            if org.esta_vivo() {
-                true
+        // REPRODUCCIÓN
+        // Solo las hembras que encuentran macho maduro de su especie pueden tener crías,
+        // y solo se aparean en su temporada de cría. Las preñadas siguen su
+        // gestación y paren aunque la temporada haya terminado.
+        let mut crias = Vec::new();
+        for t in 0..self.poblacion.herbivoros.len() {
+            let tabla = &self.poblacion.herbivoros[t];
+            let apareadas = if en_temporada(tabla.especie.temporada_cria, fecha) {
+                self.elegir_apareadas(t)
+            } else if tabla.especie.ciclo.is_some() {
+                vec![None; tabla.individuos.len()]
             } else {
-                muertos += 1;
-                println!("☠️ {} murió a los {} días (peso final {:.2})",
-                         org.nombre(), org.edad(), org.peso());
-                false
+                continue;
+            };
+            for (herbivoro, pareja) in self.poblacion.herbivoros[t].individuos.iter_mut().zip(apareadas) {
+                crias.extend(herbivoro.reproducirse(pareja, &mut self.rng));
+            }
+        }
+
+        // Los lobos se reproducen en pareja.
+        let cachorros = self.reproducir_lobos(dia, fecha, &mut pendientes);
+
+        for evento in pendientes {
+            self.emitir(evento);
+        }
+
+        // Agrega todas las nuevas crías, cada una con su ID.
+        for cria in crias {
+            self.nacer(dia, cria);
+        }
+        for cachorro in cachorros {
+            self.nacer(dia, cachorro);
+        }
+
+        // CAZA DE TODOS LOS LOBOS SEGÚN RESERVA
+        // Cada lobo vivo caza una vez al día, en un orden aleatorio distinto cada día
+        // para que ninguno tenga siempre la primera opción sobre las presas.
+        let mut lobos: Vec<usize> = (0..self.poblacion.lobos.len()).collect();
+        lobos.shuffle(&mut self.rng);
+
+        if self.poblacion.lobos.iter().any(Lobo::sale_a_cazar) {
+            let mut presas = Presas::new(&self.poblacion, self.espacio, &mut self.rng);
+            for l in lobos {
+                muertos += self.cazar(dia, l, &mut presas);
+            }
+
+            // Se retiran todas las presas cazadas de una sola pasada por especie.
+            for (tabla, cazadas) in self.poblacion.herbivoros.iter_mut().zip(presas.cazadas) {
+                let mut cazadas = cazadas.into_iter();
+                tabla.individuos.retain(|_| !cazadas.next().unwrap_or(false));
+            }
+        }
+
+        self.historial.cerrar_dia(dia, &self.poblacion, self.vegetacion.biomasa(), self.calendario.as_ref());
+        let poblacion = self.poblacion.len();
+        self.emitir(Evento::DiaTerminado { dia, muertes: muertos, poblacion });
+    }
+
+    // Agrega una cría recién nacida y avisa su nacimiento.
+    fn nacer<T: Habitante>(&mut self, dia: u32, cria: T) {
+        let especie = cria.nombre().to_string();
+        let Identidad { madre, padre, .. } = *cria.identidad();
+        let id = self.agregar(cria);
+        self.emitir(Evento::Nacimiento { dia, id, especie, madre, padre });
+    }
+
+    // Para cada herbívoro de la tabla `t`, el ID del macho que lo cubre hoy
+    // (solo hembras maduras en celo que consiguen pareja). El sistema de apareamiento
+    // de la especie decide cuántas hembras puede cubrir cada macho maduro;
+    // hembras y machos se emparejan al azar. Con espacio, cada hembra solo
+    // puede aparearse con un macho cercano. En monogamia las parejas ya
+    // formadas solo se aparean entre sí, y las nuevas quedan unidas.
+    fn elegir_apareadas(&mut self, t: usize) -> Vec<Option<u64>> {
+        let monogamia = self.poblacion.herbivoros[t].especie.apareamiento == SistemaApareamiento::Monogamia;
+        if monogamia {
+            enviudar(&mut self.poblacion.herbivoros[t]);
+        }
+        let tabla = &self.poblacion.herbivoros[t];
+        let alcanza = |a: Posicion, b: Posicion| self.espacio.is_none_or(|e| e.distancia(a, b) <= e.radio_apareamiento);
+
+        let mut apareadas = vec![None; tabla.individuos.len()];
+        let mut machos: Vec<(usize, Posicion)> = Vec::new();
+        let mut hembras: Vec<usize> = Vec::new();
+        for (i, herbivoro) in tabla.individuos.iter().enumerate() {
+            if !herbivoro.en_celo() {
+                continue;
+            }
+            if let Some(pareja) = herbivoro.pareja {
+                if herbivoro.sexo == Sexo::Hembra
+                    && let Some(macho) = tabla.buscar(pareja)
+                    && macho.en_celo()
+                    && alcanza(herbivoro.posicion, macho.posicion)
+                {
+                    apareadas[i] = Some(pareja);
+                }
+                continue;
+            }
+            match herbivoro.sexo {
+                Sexo::Macho => machos.push((i, herbivoro.posicion)),
+                Sexo::Hembra => hembras.push(i),
+            }
+        }
+
+        if machos.is_empty() || hembras.is_empty() {
+            return apareadas;
+        }
+        hembras.shuffle(&mut self.rng);
+        machos.shuffle(&mut self.rng);
+
+        // Cada hembra toma el primer macho a su alcance que aún tenga cupo.
+        let cupo = tabla.especie.apareamiento.hembras_por_macho();
+        let mut nuevas: Vec<(usize, usize)> = Vec::new();
+        match self.espacio {
+            // Sin espacio todos se alcanzan: cada macho cubre `cupo` hembras seguidas.
+            None => {
+                for (k, i) in hembras.into_iter().enumerate() {
+                    let Some(&(macho, _)) = machos.get(k / cupo) else { break };
+                    nuevas.push((i, macho));
+                }
+            }
+            Some(espacio) => {
+                let rejilla = Rejilla::new(espacio, machos.iter().map(|m| m.1));
+                let mut cupos = vec![cupo; machos.len()];
+                for i in hembras {
+                    let elegido = rejilla.cerca(tabla.individuos[i].posicion, espacio.radio_apareamiento)
+                        .filter(|&m| cupos[m] > 0)
+                        .min();
+                    if let Some(m) = elegido {
+                        cupos[m] -= 1;
+                        nuevas.push((i, machos[m].0));
+                    }
+                }
+            }
+        }
+
+        let individuos = &mut self.poblacion.herbivoros[t].individuos;
+        for (hembra, macho) in nuevas {
+            let (id_hembra, id_macho) = (individuos[hembra].identidad.id, individuos[macho].identidad.id);
+            apareadas[hembra] = Some(id_macho);
+            if monogamia {
+                individuos[hembra].pareja = Some(id_macho);
+                individuos[macho].pareja = Some(id_hembra);
+            }
+        }
+        apareadas
+    }
+
+    // Las lobas preñadas avanzan su gestación; después se forman parejas
+    // macho/hembra entre los lobos maduros (en orden aleatorio, cada lobo en
+    // una sola pareja; las hembras, solo en celo y en su temporada de cría).
+    // Devuelve los cachorros nacidos; los que la madre no pudo alimentar al
+    // parir se anotan en `pendientes` como camada perdida.
+    fn reproducir_lobos(&mut self, dia: u32, fecha: Option<Fecha>, pendientes: &mut Vec<Evento>) -> Vec<Lobo> {
+        let mut crias = Vec::new();
+        for lobo in &mut self.poblacion.lobos {
+            let (nacidos, perdidos) = lobo.gestar(&mut self.rng);
+            crias.extend(nacidos);
+            if perdidos > 0 {
+                pendientes.push(Evento::CamadaPerdida {
+                    dia,
+                    madre: lobo.identidad.id,
+                    especie: lobo.nombre().to_string(),
+                    crias: perdidos,
+                    causa: CausaMuerte::Hambre,
+                });
+            }
+        }
+
+        let mut hembras = Vec::new();
+        let mut machos = Vec::new();
+        for (i, lobo) in self.poblacion.lobos.iter().enumerate() {
+            if lobo.en_celo() && (lobo.sexo == Sexo::Macho || en_temporada(lobo.temporada_cria, fecha)) {
+                match lobo.sexo {
+                    Sexo::Hembra => hembras.push(i),
+                    Sexo::Macho => machos.push(i),
+                }
+            }
+        }
+        hembras.shuffle(&mut self.rng);
+        machos.shuffle(&mut self.rng);
+
+        // Cada hembra se empareja con el primer macho libre a su alcance.
+        let lobos = &self.poblacion.lobos;
+        let parejas: Vec<(usize, usize)> = match self.espacio {
+            None => hembras.into_iter().zip(machos).collect(),
+            Some(espacio) => {
+                let rejilla = Rejilla::new(espacio, machos.iter().map(|&m| lobos[m].posicion));
+                let mut libres = vec![true; machos.len()];
+                let mut parejas = Vec::new();
+                for h in hembras {
+                    let elegido = rejilla.cerca(lobos[h].posicion, espacio.radio_apareamiento)
+                        .filter(|&k| libres[k])
+                        .min();
+                    if let Some(k) = elegido {
+                        libres[k] = false;
+                        parejas.push((h, machos[k]));
+                    }
+                }
+                parejas
+            }
+        };
+
+        for (h, m) in parejas {
+            let Ok([madre, padre]) = self.poblacion.lobos.get_disjoint_mut([h, m]) else { continue };
+            crias.extend(madre.criar(padre, &mut self.rng));
+        }
+        crias
+    }
+
+    // La caza del día de un lobo: sin respuesta funcional, un ataque si
+    // tiene hambre; con ella, los que da la curva para las presas a su
+    // alcance y los lobos que cazan a su lado. Devuelve cuántas presas cazó.
+    fn cazar(&mut self, dia: u32, l: usize, presas: &mut Presas) -> usize {
+        let lobo = &self.poblacion.lobos[l];
+        if !lobo.sale_a_cazar() {
+            return 0;
+        }
+        let ataques = match lobo.respuesta {
+            None => 1,
+            Some(respuesta) => {
+                let (disponibles, lobos) = presas.densidad(&self.poblacion, lobo);
+                let esperados = if disponibles == 0 { 0.0 } else { respuesta.ataques(disponibles as f64, lobos as f64) };
+                let id = lobo.identidad.id;
+                self.emitir(Evento::Busqueda {
+                    dia,
+                    id,
+                    especie: "Lobo".to_string(),
+                    presas: disponibles,
+                    depredadores: lobos,
+                    esperados,
+                });
+                if disponibles == 0 {
+                    1 // para que avise si se queda sin comer
+                } else {
+                    // La parte entera siempre; un ataque más con la probabilidad de la fracción.
+                    esperados as usize + usize::from(self.rng.random_bool(esperados.fract()))
+                }
+            }
+        };
+        let mut cazadas = 0;
+        for _ in 0..ataques {
+            match self.atacar(dia, l, presas) {
+                Some(true) => cazadas += 1,
+                Some(false) => {}
+                None => break,
+            }
+        }
+        cazadas
+    }
+
+    // Un lobo ataca una presa que nadie haya tomado hoy.
+    // Devuelve si la cazó, o None si no encontró ninguna.
+    fn atacar(&mut self, dia: u32, l: usize, presas: &mut Presas) -> Option<bool> {
+        let lobo = &self.poblacion.lobos[l];
+        let reserva = lobo.reserva;
+        let lobo_id = lobo.identidad.id;
+
+        // La estrategia del lobo elige la presa dentro de cada especie y
+        // después la especie entre las que tienen presas disponibles.
+        let opciones = presas.mejores(&self.poblacion, lobo, &mut self.rng);
+        if opciones.is_empty() {
+            if lobo.necesita_cazar() {
+                self.emitir(Evento::Inanicion { dia, id: lobo_id, especie: "Lobo".to_string(), reserva });
+            }
+            return None;
+        }
+        let ofertas: Vec<Oferta> = opciones.iter().map(|o| Oferta {
+            presa: &self.poblacion.herbivoros[o.especie].individuos[o.indice],
+            disponibles: o.disponibles,
+        }).collect();
+        let i = lobo.estrategia.elegir_especie(&ofertas, &mut self.rng)?;
+        let (especie, j) = (opciones[i].especie, opciones[i].indice);
+        let presa = &self.poblacion.herbivoros[especie].individuos[j];
+        let especie_presa = presa.especie.nombre.clone();
+        let (presa_id, edad_presa, peso_presa) = (presa.identidad.id, presa.edad, presa.peso);
+        let gestando = presa.gestando();
+
+        // El ataque puede fallar: la presa escapa (y otro lobo todavía puede
+        // intentarlo) y el lobo gasta parte de su reserva en la persecución.
+        if let Some(exito) = lobo.exito_caza
+            && !self.rng.random_bool(exito.probabilidad(lobo, presa)) {
+            let costo = self.poblacion.lobos[l].gastar(exito.costo_fallo);
+            self.emitir(Evento::Escape {
+                dia,
+                depredador_id: lobo_id,
+                depredador: "Lobo".to_string(),
+                presa_id,
+                presa: especie_presa,
+                costo,
+            });
+            return Some(false);
+        }
+
+        //  Marcar la presa y darle comida al lobo 
+        presas.cazar(&self.poblacion, especie, j);
+        self.poblacion.lobos[l].agregar_comida(peso_presa);
+
+        self.emitir(Evento::Caza {
+            dia,
+            depredador_id: lobo_id,
+            depredador: "Lobo".to_string(),
+            presa_id,
+            presa: especie_presa.clone(),
+            peso: peso_presa,
+        });
+        self.emitir(Evento::Muerte {
+            dia,
+            id: presa_id,
+            especie: especie_presa.clone(),
+            edad: edad_presa,
+            peso: peso_presa,
+            causa: CausaMuerte::Depredacion,
+        });
+        if gestando > 0 {
+            self.emitir(Evento::CamadaPerdida {
+                dia,
+                madre: presa_id,
+                especie: especie_presa,
+                crias: gestando,
+                causa: CausaMuerte::Depredacion,
+            });
+        }
+        Some(true)
+    }
+}
+
+// Si la fecha cae en la temporada de cría (sin calendario o sin temporada, siempre).
+fn en_temporada(temporada: Option<Temporada>, fecha: Option<Fecha>) -> bool {
+    match (temporada, fecha) {
+        (Some(temporada), Some(fecha)) => temporada.contiene(fecha),
+        _ => true,
+    }
+}
+
+// Cuántas presas de la lista ordenada (con sus `edades` ascendentes) tienen
+// al menos `edad_minima` y no están entre las `bajas` del día.
+fn con_edad(edades: &[u32], bajas: &[u32], edad_minima: u32) -> usize {
+    let con_edad = edades.len() - edades.partition_point(|&edad| edad < edad_minima);
+    con_edad - bajas.iter().filter(|&&edad| edad >= edad_minima).count()
+}
+
+// Deshace las parejas cuyo otro miembro ya no está en la tabla (murió o
+// fue cazado): quien queda puede volver a formar pareja.
+fn enviudar(tabla: &mut TablaHerbivoros) {
+    let viudos: Vec<usize> = tabla.individuos.iter().enumerate()
+        .filter(|(_, h)| h.pareja.is_some_and(|p| tabla.buscar(p).is_none()))
+        .map(|(i, _)| i)
+        .collect();
+    for i in viudos {
+        tabla.individuos[i].pareja = None;
+    }
+}
+
+// Si el organismo murió, anota su muerte (y la de su camada, si estaba
+// preñada) y devuelve false para sacarlo de su tabla.
+fn sigue_vivo(org: &dyn Organismo, dia: u32, muertos: &mut usize, pendientes: &mut Vec<Evento>) -> bool {
+    if org.esta_vivo() {
+        return true;
+    }
+    *muertos += 1;
+    // Cada forma de morir anota su causa; `Estado::restaurar` rechaza a los muertos sin ella.
+    let causa = org.causa_muerte().expect("un organismo muerto siempre tiene causa");
+    pendientes.push(Evento::Muerte {
+        dia,
+        id: org.id(),
+        especie: org.nombre().to_string(),
+        edad: org.edad(),
+        peso: org.peso(),
+        causa,
+    });
+    // Con la madre muere la camada que llevaba.
+    if org.gestando() > 0 {
+        pendientes.push(Evento::CamadaPerdida {
+            dia,
+            madre: org.id(),
+            especie: org.nombre().to_string(),
+            crias: org.gestando(),
+            causa,
+        });
+    }
+    false
+}
+
+// Lo que ve cada organismo al moverse: la foto de antes de que nadie se mueva.
+struct Vista<'a> {
+    espacio: Espacio,
+    vegetacion: &'a Vegetacion,
+    // Por celda, el lobo más cercano a la vista.
+    lobos: Vec<Option<Posicion>>,
+    // Por edad mínima de presa, la presa más cercana a la vista desde cada celda.
+    presas: Vec<(u32, Vec<Option<Posicion>>)>,
+}
+
+impl Vista<'_> {
+    // Próxima celda de quien está en `pos`; `caza` es la edad mínima de sus
+    // presas si es un depredador. Sin objetivo a la vista, un paso al azar.
+    fn paso(&self, pos: Posicion, movimiento: Movimiento, caza: Option<u32>, rng: &mut Generador) -> Posicion {
+        let espacio = &self.espacio;
+        let objetivo = match (movimiento, caza) {
+            (Movimiento::Aleatorio, _) => None,
+            (Movimiento::HaciaComida, Some(edad_minima)) => self.presas.iter()
+                .find(|(edad, _)| *edad == edad_minima)
+                .and_then(|(_, cercanas)| cercanas[espacio.celda(pos)])
+                .map(|p| espacio.paso_hacia(pos, p)),
+            (Movimiento::HaciaComida, None) => {
+                // La vecina con más pasto; si hay empate, una al azar.
+                let mut vecinas = [(pos, 0.0); 9];
+                for (vecina, p) in vecinas.iter_mut().zip(espacio.vecindario(pos)) {
+                    *vecina = (p, self.vegetacion.celdas[espacio.celda(p)]);
+                }
+                let max = vecinas.iter().map(|v| v.1).fold(0.0, f64::max);
+                let mejores = || vecinas.iter().filter(|v| v.1 == max).map(|v| v.0);
+                mejores().nth(rng.random_range(0..mejores().count()))
+            }
+            (Movimiento::HuirDepredadores, _) => {
+                self.lobos[espacio.celda(pos)].map(|l| espacio.paso_lejos(pos, l))
             }
+        };
+        objetivo.unwrap_or_else(|| espacio.paso_aleatorio(pos, rng))
+    }
+}
+
+// Lo que los lobos consultan al cazar, armado una sola vez por día.
+struct Presas {
+    // Por especie, las que ya cazó algún lobo hoy: siguen en su tabla
+    // (para no mover los índices) hasta que termina la caza.
+    cazadas: Vec<Vec<bool>>,
+    // Por especie, la edad de cada presa cazada hoy.
+    bajas: Vec<Vec<u32>>,
+    busquedas: Vec<Busqueda>,
+    // Con espacio: dónde está cada lobo, para contar los que cazan cerca.
+    lobos: Option<(Rejilla, u32)>,
+}
+
+// Cómo se encuentran las presas de una especie.
+enum Busqueda {
+    // Sin espacio: por cada estrategia de los lobos con hambre, las presas
+    // que algún lobo puede cazar por su edad, de mayor a menor puntaje, y
+    // las edades de todas ellas (ascendentes) para contar las disponibles.
+    Ordenadas { ordenes: Vec<(Estrategia, Orden)>, edades: Vec<u32> },
+    // Con espacio: dónde está cada una, para ver solo las cercanas al lobo.
+    EnGrilla { rejilla: Rejilla, radio: u32 },
+}
+
+// Las presas de una especie en el orden de una estrategia (las que tienen
+// el mismo puntaje, en orden aleatorio) y desde dónde mirar: las de antes
+// ya están todas cazadas.
+struct Orden {
+    presas: Vec<usize>,
+    primera: usize,
+}
+
+// La presa que un lobo elegiría dentro de una especie.
+struct Opcion {
+    especie: usize,
+    indice: usize,
+    disponibles: usize,
+}
+
+impl Presas {
+    fn new(poblacion: &Poblacion, espacio: Option<Espacio>, rng: &mut Generador) -> Self {
+        let cazadas = poblacion.herbivoros.iter().map(|t| vec![false; t.individuos.len()]).collect();
+        let bajas = vec![Vec::new(); poblacion.herbivoros.len()];
+        let edad_minima = poblacion.lobos.iter().map(|l| l.edad_minima_presa).min().unwrap_or(0);
+        let mut estrategias: Vec<Estrategia> = Vec::new();
+        for lobo in poblacion.lobos.iter().filter(|l| l.sale_a_cazar()) {
+            if !estrategias.contains(&lobo.estrategia) {
+                estrategias.push(lobo.estrategia);
+            }
+        }
+        let busquedas = poblacion.herbivoros.iter().map(|tabla| match espacio {
+            None => {
+                let aptas: Vec<usize> = (0..tabla.individuos.len())
+                    .filter(|&j| tabla.individuos[j].edad >= edad_minima)
+                    .collect();
+                let ordenes = estrategias.iter().map(|&estrategia| {
+                    let mut presas = aptas.clone();
+                    // Mezclar antes de ordenar (el orden es estable) sortea los empates.
+                    presas.shuffle(rng);
+                    let puntajes: Vec<f64> = tabla.individuos.iter().map(|h| estrategia.puntaje(h)).collect();
+                    presas.sort_by(|&a, &b| puntajes[b].total_cmp(&puntajes[a]));
+                    (estrategia, Orden { presas, primera: 0 })
+                }).collect();
+                let mut edades: Vec<u32> = aptas.iter().map(|&j| tabla.individuos[j].edad).collect();
+                edades.sort_unstable();
+                Busqueda::Ordenadas { ordenes, edades }
+            }
+            Some(espacio) => Busqueda::EnGrilla {
+                rejilla: Rejilla::new(espacio, tabla.individuos.iter().map(|h| h.posicion)),
+                radio: espacio.radio_caza,
+            },
+        }).collect();
+        let lobos = espacio.map(|espacio| {
+            (Rejilla::new(espacio, poblacion.lobos.iter().map(|l| l.posicion)), espacio.radio_caza)
         });
+        Presas { cazadas, bajas, busquedas, lobos }
+    }
+
+    // Presas disponibles para el lobo (de todas las especies) y lobos que
+    // cazan en el mismo lugar, contándolo a él: la densidad que ve su
+    // respuesta funcional. Sin espacio, todos comparten el mismo lugar.
+    fn densidad(&self, poblacion: &Poblacion, lobo: &Lobo) -> (usize, usize) {
+        let mut disponibles = 0;
+        for (e, tabla) in poblacion.herbivoros.iter().enumerate() {
+            disponibles += match &self.busquedas[e] {
+                Busqueda::Ordenadas { edades, .. } => con_edad(edades, &self.bajas[e], lobo.edad_minima_presa),
+                Busqueda::EnGrilla { rejilla, radio } => rejilla.cerca(lobo.posicion, *radio)
+                    .filter(|&j| !self.cazadas[e][j] && tabla.individuos[j].edad >= lobo.edad_minima_presa)
+                    .count(),
+            };
+        }
+        let lobos = match &self.lobos {
+            None => poblacion.lobos.len(),
+            Some((rejilla, radio)) => rejilla.cerca(lobo.posicion, *radio).count(),
+        };
+        (disponibles, lobos)
+    }
 
-        self.poblacion.extend(nuevos); // Agrega todas las nuevas crías.
-
-        // CAZA DEL LOBO SEGÚN RESERVA
-        // Busca el lobo para que cace.
-        if let Some(lobo_idx) = self.poblacion.iter().position(|org| org.nombre() == "Lobo" && org.esta_vivo()) {
-            if let Some(lobo_ref) = self.poblacion[lobo_idx].as_any().downcast_ref::<crate::lobo::Lobo>() {
-                if lobo_ref.necesita_cazar() && !lobo_ref.tiene_reserva_llena() {
-                    // Selecciona las presas disponibles (cabras y conejos maduros).
-                    let presas_cabras: Vec<(usize, f64)> = self.poblacion.iter().enumerate()
-                        .filter(|(_, org)| org.nombre() == "Cabra" && org.edad() >= 50)
-                        .map(|(j, org)| (j, org.peso()))
-                        .collect();
-
-                    let presas_conejos: Vec<(usize, f64)> = self.poblacion.iter().enumerate()
-                        .filter(|(_, org)| org.nombre() == "Conejo" && org.edad() >= 50)
-                        .map(|(j, org)| (j, org.peso()))
-                        .collect();
-
-                    // Elige la especie a cazar de forma aleatoria si ambas están disponibles.
-                    let especie_objetivo = if !presas_cabras.is_empty() && !presas_conejos.is_empty() {
-                        if rng.gen_bool(0.5) { "Cabra" } else { "Conejo" }
-                    } else if !presas_cabras.is_empty() {
-                        "Cabra"
-                    } else if !presas_conejos.is_empty() {
-                        "Conejo"
-                    } else {
-                        ""
-                    };
-
-                    if especie_objetivo != "" {
-                        let presas = if especie_objetivo == "Cabra" { presas_cabras } else { presas_conejos };
-
-                        //  Buscar la presa más pesada 
-                        let max_peso = presas.iter().map(|(_, p)| *p).fold(0.0, f64::max);
-                        let candidatas: Vec<_> = presas.into_iter().filter(|(_, p)| *p == max_peso).collect();
-
-                        //  Si hay empate, elegir al azar 
-                        let (idx, _) = candidatas[rng.gen_range(0..candidatas.len())];
-
-                        //  Eliminar presa y darle comida al lobo 
-                        let presa = self.poblacion.remove(idx);
-                        let peso_presa = presa.peso();
-
-                        // Se busca al lobo de nuevo con una referencia mutable para actualizar su reserva.
-                        if let Some(lobo_mut) = self.poblacion.iter_mut().find(|o| o.nombre() == "Lobo" && o.esta_vivo()) {
-                            if let Some(l) = lobo_mut.as_any_mut().downcast_mut::<crate::lobo::Lobo>() {
-                                l.agregar_comida(peso_presa);
-                                let mensaje = format!(" El lobo cazó un {} de {:.2} kg", presa.nombre(), peso_presa);
-                                println!("{}", mensaje);
-                                self.registrar_evento(mensaje);
+    // Por cada especie con presas disponibles para el lobo (con edad
+    // suficiente, aún no cazadas y, con espacio, a su alcance), la de mayor
+    // puntaje según su estrategia; si varias empatan, una de ellas al azar.
+    fn mejores(&mut self, poblacion: &Poblacion, lobo: &Lobo, rng: &mut Generador) -> Vec<Opcion> {
+        let mut opciones = Vec::new();
+        for (e, tabla) in poblacion.herbivoros.iter().enumerate() {
+            let cazadas = &self.cazadas[e];
+            let apta = |j: usize| !cazadas[j] && tabla.individuos[j].edad >= lobo.edad_minima_presa;
+            let (elegida, disponibles) = match &mut self.busquedas[e] {
+                Busqueda::Ordenadas { ordenes, edades } => {
+                    let Some((_, Orden { presas, primera })) = ordenes.iter_mut()
+                        .find(|(estrategia, _)| *estrategia == lobo.estrategia) else { continue };
+                    while *primera < presas.len() && cazadas[presas[*primera]] {
+                        *primera += 1;
+                    }
+                    let elegida = presas[*primera..].iter().copied().find(|&j| apta(j));
+                    (elegida, con_edad(edades, &self.bajas[e], lobo.edad_minima_presa))
+                }
+                Busqueda::EnGrilla { rejilla, radio } => {
+                    // Entre las empatadas, cada una reemplaza a la elegida con
+                    // probabilidad 1/k: al final todas tuvieron la misma chance.
+                    let mut elegida = None;
+                    let (mut max, mut empatadas, mut disponibles) = (f64::NEG_INFINITY, 0, 0);
+                    for j in rejilla.cerca(lobo.posicion, *radio).filter(|&j| apta(j)) {
+                        disponibles += 1;
+                        let puntaje = lobo.estrategia.puntaje(&tabla.individuos[j]);
+                        if puntaje > max {
+                            (max, empatadas) = (puntaje, 0);
+                        }
+                        if puntaje == max {
+                            empatadas += 1;
+                            if rng.random_range(0..empatadas) == 0 {
+                                elegida = Some(j);
                             }
                         }
                     }
-                } else {
-                    println!(" El lobo no cazó hoy (reserva: {:.2})", lobo_ref.reserva);
+                    (elegida, disponibles)
+                }
+            };
+            if let Some(indice) = elegida {
+                opciones.push(Opcion { especie: e, indice, disponibles });
+            }
+        }
+        opciones
+    }
+
+    // Marca la presa como cazada.
+    fn cazar(&mut self, poblacion: &Poblacion, especie: usize, j: usize) {
+        self.cazadas[especie][j] = true;
+        self.bajas[especie].push(poblacion.herbivoros[especie].individuos[j].edad);
+    }
+}
+
+impl Default for Simulador {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::argumentos::PoblacionInicial;
+    use crate::escenario::Escenario;
+    use crate::herbivoro::{Herbivoro, ParametrosHerbivoro};
+    use crate::caza::ExitoCaza;
+    use crate::lobo::ParametrosLobo;
+    use crate::modelo::CurvaCrecimiento;
+    use crate::reproduccion::Ciclo;
+    use std::cell::RefCell;
+    use std::collections::{HashMap, HashSet};
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    // Un simulador del escenario que junta todos sus eventos.
+    fn con_eventos(escenario: &Escenario, semilla: u64) -> (Simulador, Rc<RefCell<Vec<Evento>>>) {
+        let mut sim = escenario.crear_simulador(Some(semilla));
+        let eventos = Rc::new(RefCell::new(Vec::new()));
+        let buzon = Rc::clone(&eventos);
+        sim.suscribir(move |evento: &Evento| buzon.borrow_mut().push(evento.clone()));
+        (sim, eventos)
+    }
+
+    // Corre `dias` días y devuelve los eventos y el estado final en texto.
+    fn correr(escenario: &Escenario, semilla: u64, dias: u32) -> (Vec<Evento>, String) {
+        let (mut sim, eventos) = con_eventos(escenario, semilla);
+        for dia in 1..=dias {
+            sim.simular_dia(dia);
+        }
+        let eventos = eventos.borrow().clone();
+        (eventos, crate::estado::Estado::de(&sim).a_json())
+    }
+
+    #[test]
+    fn la_misma_semilla_repite_la_corrida() {
+        let mut escenario = Escenario { espacio: Some(crate::espacio::Espacio::default()), ..Escenario::default() };
+        escenario.poblacion.insert("Lobo".to_string(), PoblacionInicial { aleatorios: 4, ..Default::default() });
+        let (eventos, estado) = correr(&escenario, 42, 60);
+        assert!(eventos.len() > 60);
+        assert_eq!(correr(&escenario, 42, 60), (eventos.clone(), estado.clone()));
+        assert_ne!(correr(&escenario, 43, 60).1, estado);
+    }
+
+    #[test]
+    fn en_monogamia_las_parejas_duran_hasta_que_uno_muere() {
+        let conejo = ParametrosHerbivoro { apareamiento: SistemaApareamiento::Monogamia, ..ParametrosHerbivoro::conejo() };
+        let escenario = Escenario {
+            herbivoros: vec![conejo],
+            poblacion: BTreeMap::from([("Conejo".to_string(), PoblacionInicial { aleatorios: 40, ..Default::default() })]),
+            ..Escenario::default()
+        };
+        let (mut sim, eventos) = con_eventos(&escenario, 5);
+
+        let mut muerte: HashMap<u64, u32> = HashMap::new();
+        let mut padre_de: HashMap<u64, u64> = HashMap::new();
+        let mut madre_de: HashMap<u64, u64> = HashMap::new();
+        let mut nacimientos = 0;
+        for dia in 1..=400 {
+            sim.simular_dia(dia);
+            for evento in eventos.borrow_mut().drain(..) {
+                match evento {
+                    Evento::Muerte { id, dia, .. } => {
+                        muerte.insert(id, dia);
+                    }
+                    Evento::Nacimiento { madre: Some(madre), padre: Some(padre), dia, .. } => {
+                        nacimientos += 1;
+                        // Cambiar de pareja solo se puede si la anterior murió.
+                        if let Some(anterior) = padre_de.insert(madre, padre) {
+                            assert!(anterior == padre || muerte.get(&anterior).is_some_and(|&d| d <= dia));
+                        }
+                        if let Some(anterior) = madre_de.insert(padre, madre) {
+                            assert!(anterior == madre || muerte.get(&anterior).is_some_and(|&d| d <= dia));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            // Las parejas son recíprocas.
+            let tabla = &sim.poblacion.herbivoros[0];
+            for h in &tabla.individuos {
+                if let Some(p) = h.pareja {
+                    let pareja = tabla.buscar(p).expect("la pareja está viva");
+                    assert_eq!(pareja.pareja, Some(h.identidad.id));
+                    assert_ne!(pareja.sexo, h.sexo);
                 }
             }
         }
+        assert!(nacimientos > 50, "solo {} nacimientos", nacimientos);
+    }
+
+    // En qué momento del día se emite cada evento: pastoreo, envejecer,
+    // nacimientos, caza y cierre.
+    fn fase(evento: &Evento) -> u8 {
+        match evento {
+            Evento::Pastoreo { .. } => 0,
+            Evento::Muerte { causa: CausaMuerte::Depredacion, .. }
+            | Evento::CamadaPerdida { causa: CausaMuerte::Depredacion, .. } => 3,
+            Evento::Alimentacion { .. } | Evento::Muerte { .. } | Evento::CamadaPerdida { .. } => 1,
+            Evento::Nacimiento { .. } => 2,
+            Evento::Busqueda { .. } | Evento::Caza { .. } | Evento::Escape { .. } | Evento::Inanicion { .. } => 3,
+            Evento::DiaTerminado { .. } => 4,
+        }
+    }
+
+    #[test]
+    fn los_eventos_de_un_dia_salen_en_orden() {
+        // Con poca reserva los lobos salen a cazar desde el principio.
+        let mut escenario = Escenario { lobo: ParametrosLobo { reserva_inicial: 60.0, ..Default::default() }, ..Escenario::default() };
+        escenario.poblacion.insert("Lobo".to_string(), PoblacionInicial { aleatorios: 6, ..Default::default() });
+        let (eventos, _) = correr(&escenario, 7, 365);
+
+        let mut dias = eventos.split_inclusive(|e| matches!(e, Evento::DiaTerminado { .. }));
+        let mut vistas = [false; 5];
+        for dia in 1..=365 {
+            let del_dia = dias.next().expect("cada día termina con su evento");
+            assert!(del_dia.iter().all(|e| e.dia() == dia));
+            assert!(del_dia.is_sorted_by_key(fase), "día {}: {:?}", dia, del_dia);
+            // Cada muerte del día, por la causa que sea, cuenta en el cierre.
+            let muertes = del_dia.iter().filter(|e| matches!(e, Evento::Muerte { .. })).count();
+            assert!(matches!(del_dia.last(), Some(&Evento::DiaTerminado { muertes: m, .. }) if m == muertes));
+            // Cada caza va seguida de la muerte de su presa.
+            for par in del_dia.windows(2) {
+                if let Evento::Caza { presa_id, .. } = par[0] {
+                    assert!(matches!(par[1], Evento::Muerte { id, causa: CausaMuerte::Depredacion, .. } if id == presa_id));
+                }
+            }
+            for e in del_dia {
+                vistas[fase(e) as usize] = true;
+            }
+        }
+        assert!(dias.next().is_none());
+        assert_eq!(vistas, [true; 5]);
+    }
 
-        // --- REPORTE ---
-        let mut especies: HashMap<&str, Vec<&Box<dyn Organismo>>> = HashMap::new();
-        for org in &self.poblacion {
-            especies.entry(org.nombre()).or_default().push(org);
+    // Lobos con hambre y cabras adultas que no mueren más que cazadas.
+    fn manada(lobos: usize, cabras: usize, semilla: u64) -> (Simulador, Rc<RefCell<Vec<Evento>>>) {
+        let mut sim = Simulador::con_semilla(semilla);
+        let cabra = Arc::new(ParametrosHerbivoro { mortalidad_diaria: 0.0, ..ParametrosHerbivoro::cabra() });
+        for i in 0..cabras {
+            let mut h = Herbivoro::new(Arc::clone(&cabra), if i % 2 == 0 { Sexo::Hembra } else { Sexo::Macho });
+            h.edad = 200;
+            h.peso = cabra.crecimiento.peso(200.0);
+            sim.agregar(h);
         }
+        let hambre = ParametrosLobo { reserva_inicial: 10.0, ..Default::default() };
+        for _ in 0..lobos {
+            sim.agregar(Lobo::con_parametros(&hambre, Sexo::Macho));
+        }
+        let eventos = Rc::new(RefCell::new(Vec::new()));
+        let buzon = Rc::clone(&eventos);
+        sim.suscribir(move |evento: &Evento| buzon.borrow_mut().push(evento.clone()));
+        (sim, eventos)
+    }
 
-        println!("\n--- Día {} ---", dia);
-        println!("Murieron {} organismos en este día.", muertos);
+    #[test]
+    fn cazan_todos_los_lobos_con_hambre() {
+        let (mut sim, eventos) = manada(6, 30, 1);
+        sim.simular_dia(1);
+        let eventos = eventos.borrow();
 
-        for (especie, lista) in &especies {
-            println!("\n {} (total: {})", especie, lista.len());
-            for (i, org) in lista.iter().enumerate() {
-                println!(
-                    "   ID {} -> (edad: {} días, peso: {:.2})",
-                    i,
-                    org.edad(),
-                    org.peso()
-                );
+        let mut cazadores = Vec::new();
+        let mut presas = HashSet::new();
+        for evento in eventos.iter() {
+            if let Evento::Caza { depredador_id, presa_id, .. } = evento {
+                cazadores.push(*depredador_id);
+                assert!(presas.insert(*presa_id), "dos lobos cazaron la cabra #{}", presa_id);
             }
         }
+        cazadores.sort();
+        let lobos: Vec<u64> = sim.poblacion.lobos.iter().map(|l| l.identidad.id).collect();
+        assert_eq!(cazadores, lobos);
+        assert_eq!(sim.contar_especie("Cabra"), 30 - presas.len());
+    }
 
-        println!("\n📊 Total población: {}\n", self.poblacion.len());
+    #[test]
+    fn las_presas_escasas_se_reparten_en_orden_aleatorio() {
+        let mut ganadores = HashSet::new();
+        for semilla in 0..20 {
+            let (mut sim, eventos) = manada(6, 2, semilla);
+            sim.simular_dia(1);
+            let eventos = eventos.borrow();
+            let cazas: Vec<u64> = eventos.iter()
+                .filter_map(|e| if let Evento::Caza { depredador_id, .. } = e { Some(*depredador_id) } else { None })
+                .collect();
+            let sin_presa = eventos.iter().filter(|e| matches!(e, Evento::Inanicion { .. })).count();
+            assert_eq!((cazas.len(), sin_presa), (2, 4));
+            ganadores.extend(cazas);
+        }
+        // Ningún lobo tiene siempre la primera opción.
+        assert_eq!(ganadores.len(), 6);
+    }
+
+    #[test]
+    fn un_ataque_fallido_le_cuesta_reserva_al_lobo_y_la_presa_sigue_viva() {
+        let exito = ExitoCaza { base: 0.3, efecto_peso: 0.0, vulnerabilidad_edad: 0.0, efecto_hambre: 0.0, costo_fallo: 1.0 };
+        let (mut sim, eventos) = manada(20, 60, 9);
+        for lobo in &mut sim.poblacion.lobos {
+            lobo.exito_caza = Some(exito);
+        }
+        sim.simular_dia(1);
+
+        let mut reservas = HashMap::new();
+        let mut cazadas = HashSet::new();
+        let mut escapadas = Vec::new();
+        for evento in eventos.borrow().iter() {
+            match *evento {
+                Evento::Alimentacion { id, reserva, .. } => {
+                    reservas.insert(id, reserva);
+                }
+                Evento::Caza { presa_id, .. } => {
+                    cazadas.insert(presa_id);
+                }
+                Evento::Escape { depredador_id, presa_id, costo, .. } => {
+                    assert_eq!(costo, reservas[&depredador_id].min(1.0));
+                    let lobo = sim.poblacion.lobos.iter().find(|l| l.identidad.id == depredador_id).unwrap();
+                    assert_eq!(lobo.reserva, reservas[&depredador_id] - costo);
+                    escapadas.push(presa_id);
+                }
+                _ => {}
+            }
+        }
+        // La que escapa sigue viva, salvo que otro lobo la cace después.
+        for presa in &escapadas {
+            assert_eq!(sim.buscar(*presa).is_some(), !cazadas.contains(presa));
+        }
+        let (cazas, escapes) = (cazadas.len(), escapadas.len());
+        assert_eq!(cazas + escapes, 20);
+        assert!(cazas > 0 && escapes > cazas);
+        let resumen = sim.historial.resumen_caza("Lobo");
+        assert_eq!((resumen.intentos, resumen.cazas), (20, cazas));
+    }
+
+    #[test]
+    fn la_camada_de_una_presa_prenada_se_pierde_con_ella() {
+        let ciclo = Ciclo { concepcion: 1.0, gestacion: 150, intervalo_partos: 240 };
+        let (mut sim, eventos) = manada(4, 4, 3);
+        for cabra in &mut sim.poblacion.herbivoros[0].individuos {
+            cabra.reproduccion.concebir(&ciclo, 99, 2);
+        }
+        sim.simular_dia(1);
+
+        let perdidas: Vec<(u64, u32)> = eventos.borrow().iter().filter_map(|e| match *e {
+            Evento::CamadaPerdida { madre, crias, causa: CausaMuerte::Depredacion, .. } => Some((madre, crias)),
+            _ => None,
+        }).collect();
+        assert_eq!(perdidas.len(), 4);
+        assert!(perdidas.iter().all(|&(madre, crias)| crias == 2 && sim.buscar(madre).is_none()));
+        assert_eq!(sim.historial.crias_perdidas("Cabra"), (8, 8));
+    }
+
+    #[test]
+    fn cada_organismo_conserva_su_id_y_sabe_quienes_son_sus_padres() {
+        let (mut sim, eventos) = con_eventos(&Escenario::default(), 21);
+        let iniciales = sim.poblacion.len() as u64;
+        let ids: Vec<u64> = sim.poblacion.herbivoros.iter().flat_map(|t| &t.individuos).map(|h| h.identidad.id)
+            .chain(sim.poblacion.lobos.iter().map(|l| l.identidad.id))
+            .collect();
+        assert_eq!(ids.iter().copied().collect::<HashSet<_>>(), (1..=iniciales).collect());
+
+        // Una cabra inicial sigue siendo la misma día tras día.
+        let cabra = sim.poblacion.herbivoros[0].individuos[5].identidad.id;
+        let mut vivos: HashMap<u64, String> = HashMap::new();
+        for h in sim.poblacion.herbivoros.iter().flat_map(|t| &t.individuos) {
+            vivos.insert(h.identidad.id, h.especie.nombre.clone());
+        }
+        for l in &sim.poblacion.lobos {
+            vivos.insert(l.identidad.id, "Lobo".to_string());
+        }
+
+        let mut ultimo = iniciales;
+        let mut nacidos = 0;
+        for dia in 1..=400 {
+            sim.simular_dia(dia);
+            if let Some(org) = sim.buscar(cabra) {
+                assert_eq!((org.identidad().id, org.edad()), (cabra, dia));
+            }
+            for evento in eventos.borrow_mut().drain(..) {
+                match evento {
+                    Evento::Nacimiento { id, especie, madre, padre, .. } => {
+                        // IDs nuevos, crecientes y nunca reutilizados.
+                        assert_eq!(id, ultimo + 1);
+                        ultimo = id;
+                        nacidos += 1;
+                        // Los padres son de su especie y estaban vivos.
+                        let (madre, padre) = (madre.unwrap(), padre.unwrap());
+                        assert_ne!(madre, padre);
+                        assert_eq!(vivos.get(&madre), Some(&especie));
+                        assert_eq!(vivos.get(&padre), Some(&especie));
+                        let cria = sim.buscar(id);
+                        assert!(cria.is_none_or(|c| c.identidad().madre == Some(madre) && c.identidad().padre == Some(padre)));
+                        vivos.insert(id, especie);
+                    }
+                    Evento::Muerte { id, .. } => {
+                        assert!(vivos.remove(&id).is_some());
+                    }
+                    _ => {}
+                }
+            }
+        }
+        assert!(nacidos > 20, "solo {} nacimientos", nacidos);
     }
 }
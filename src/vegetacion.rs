@@ -0,0 +1,87 @@
+use serde::{Deserialize, Serialize};
+
+/// Pastizal compartido por todos los herbívoros.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ParametrosVegetacion {
+    /// Biomasa máxima que sostiene el terreno (kg).
+    pub capacidad: f64,
+    /// Tasa de rebrote logístico por día.
+    pub tasa_rebrote: f64,
+    /// Biomasa que los herbívoros no pueden comer (raíces, semillas);
+    /// es lo que permite que el pasto rebrote tras un sobrepastoreo.
+    pub remanente: f64,
+    pub biomasa_inicial: f64,
+}
+
+impl Default for ParametrosVegetacion {
+    fn default() -> Self {
+        ParametrosVegetacion {
+            capacidad: 50_000.0,
+            tasa_rebrote: 0.05,
+            remanente: 500.0,
+            biomasa_inicial: 50_000.0,
+        }
+    }
+}
+
+/// Pasto del mundo. Sin espacio es una sola celda; con grilla, cada celda
+/// tiene su parte de la capacidad y rebrota por su cuenta.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Vegetacion {
+    pub celdas: Vec<f64>,
+    pub parametros: ParametrosVegetacion,
+}
+
+impl Vegetacion {
+    pub fn new(parametros: ParametrosVegetacion) -> Self {
+        Vegetacion::en_celdas(parametros, 1)
+    }
+
+    // Reparte la biomasa inicial (y la capacidad) en partes iguales entre `n` celdas.
+    pub fn en_celdas(parametros: ParametrosVegetacion, n: usize) -> Self {
+        let n = n.max(1);
+        Vegetacion { celdas: vec![parametros.biomasa_inicial / n as f64; n], parametros }
+    }
+
+    /// Biomasa total del pastizal (kg).
+    pub fn biomasa(&self) -> f64 {
+        self.celdas.iter().sum()
+    }
+
+    // Capacidad y remanente de una celda.
+    fn por_celda(&self) -> (f64, f64) {
+        let n = self.celdas.len() as f64;
+        (self.parametros.capacidad / n, self.parametros.remanente / n)
+    }
+
+    // Rebrote diario: crecimiento logístico hacia la capacidad de cada celda.
+    // `factor` escala la tasa de rebrote según la estación.
+    pub fn crecer(&mut self, factor: f64) {
+        let (capacidad, _) = self.por_celda();
+        let tasa = self.parametros.tasa_rebrote * factor;
+        for biomasa in &mut self.celdas {
+            *biomasa += tasa * *biomasa * (1.0 - *biomasa / capacidad);
+            *biomasa = biomasa.clamp(0.0, capacidad);
+        }
+    }
+
+    /// Lo que se puede comer hoy en una celda sin tocar el remanente.
+    pub fn disponible(&self, celda: usize) -> f64 {
+        let (_, remanente) = self.por_celda();
+        (self.celdas[celda] - remanente).max(0.0)
+    }
+
+    /// Los herbívoros de una celda piden `demanda` kg; devuelve lo que realmente comen.
+    pub fn pastorear(&mut self, celda: usize, demanda: f64) -> f64 {
+        let consumo = demanda.min(self.disponible(celda));
+        self.celdas[celda] -= consumo;
+        consumo
+    }
+}
+
+impl Default for Vegetacion {
+    fn default() -> Self {
+        Vegetacion::new(ParametrosVegetacion::default())
+    }
+}